@@ -0,0 +1,81 @@
+use busy_beaver::tape::{Cell, PackedTape, Tape};
+use busy_beaver::transition::Direction;
+use busy_beaver::turing_machine::TuringMachine;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Machine run by the simulation benchmark, in standard text format.
+const MACHINE: &str = "1RB1LC_1RC1RB_1RD0LE_1LA1LD_1RZ0LA";
+
+/// Step budget of the simulation benchmark.
+const MAX_STEPS: usize = 1_000_000;
+
+/// Number of operations performed by each tape benchmark iteration.
+const TAPE_OPERATIONS: usize = 10_000;
+
+fn tape_move_head(c: &mut Criterion) {
+    c.bench_function("tape_move_head", |b| {
+        b.iter(|| {
+            let mut tape = Tape::new();
+            for _ in 0..TAPE_OPERATIONS {
+                tape.move_head(black_box(Direction::Right));
+            }
+            for _ in 0..TAPE_OPERATIONS {
+                tape.move_head(black_box(Direction::Left));
+            }
+            tape
+        });
+    });
+}
+
+fn tape_read(c: &mut Criterion) {
+    let tape = Tape::new();
+
+    c.bench_function("tape_read", |b| {
+        b.iter(|| {
+            let mut ones = 0;
+            for _ in 0..TAPE_OPERATIONS {
+                ones += black_box(&tape).read();
+            }
+            ones
+        });
+    });
+}
+
+fn tape_write(c: &mut Criterion) {
+    let mut tape = Tape::new();
+
+    c.bench_function("tape_write", |b| {
+        b.iter(|| {
+            for i in 0..TAPE_OPERATIONS {
+                tape.write(black_box((i & 1) as u8));
+            }
+        });
+    });
+}
+
+fn machine_run(c: &mut Criterion) {
+    let machine = TuringMachine::<5>::from_standard_text(MACHINE).unwrap();
+
+    c.bench_function("machine_run", |b| {
+        b.iter(|| black_box(&machine).run(black_box(MAX_STEPS)));
+    });
+}
+
+fn machine_run_on<C: Cell>(c: &mut Criterion, name: &str) {
+    let machine = TuringMachine::<5>::from_standard_text(MACHINE).unwrap();
+    let mut tape = PackedTape::<C>::new();
+
+    c.bench_function(name, |b| {
+        b.iter(|| black_box(&machine).run_on(&mut tape, black_box(MAX_STEPS)));
+    });
+}
+
+fn machine_run_cell_widths(c: &mut Criterion) {
+    machine_run_on::<u32>(c, "machine_run_u32");
+    machine_run_on::<u64>(c, "machine_run_u64");
+    machine_run_on::<u128>(c, "machine_run_u128");
+}
+
+criterion_group!(tape, tape_move_head, tape_read, tape_write);
+criterion_group!(simulation, machine_run, machine_run_cell_widths);
+criterion_main!(tape, simulation);
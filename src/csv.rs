@@ -0,0 +1,342 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use crate::transition::State;
+use crate::turing_machine::RunOutcome;
+
+/// Header row of the CSV files.
+const HEADER: &str = "machine,states,steps,ones,space";
+
+/// Writes the given machines, in the standard text format, and their outcomes
+/// as CSV with a header row.
+///
+/// The columns are the machine, its number of states, and the steps, ones and
+/// space of its outcome. Machines are quoted if needed.
+///
+/// # Errors
+/// Returns an `io::Error` if writing fails.
+pub fn write_csv<W: Write>(records: &[(String, RunOutcome)], mut w: W) -> io::Result<()> {
+    writeln!(w, "{}", HEADER)?;
+
+    for (machine, outcome) in records {
+        writeln!(
+            w,
+            "{},{},{},{},{}",
+            quote(machine),
+            machine.split('_').count(),
+            outcome.steps,
+            outcome.ones,
+            outcome.space
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Reads machines and their outcomes from CSV written by [`write_csv`].
+///
+/// Quoted fields may contain commas and escaped quotes but not line breaks.
+/// Empty lines are skipped. Neither the halting state nor the write counts are
+/// stored so the outcomes are read as halting in the primary halting state
+/// without any write.
+///
+/// # Errors
+/// Returns a `CsvError` if reading fails, if the header row is missing or if
+/// a row is malformed.
+pub fn read_csv<R: Read>(r: R) -> Result<Vec<(String, RunOutcome)>, CsvError> {
+    let mut lines = BufReader::new(r).lines();
+
+    let header = lines.next().transpose()?;
+    if header.map_or(true, |header| header.trim_end() != HEADER) {
+        return Err(CsvError::MissingHeader);
+    }
+
+    let mut records = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // Line numbers start at 1 and the header is on the first line
+        records.push(parse_record(&line, index + 2)?);
+    }
+
+    Ok(records)
+}
+
+/// Parses a row of the given line number into a machine and its outcome.
+fn parse_record(line: &str, line_number: usize) -> Result<(String, RunOutcome), CsvError> {
+    let fields = split_fields(line).ok_or(CsvError::InvalidRow { line: line_number })?;
+    if fields.len() != 5 {
+        return Err(CsvError::InvalidRow { line: line_number });
+    }
+
+    let number = |index: usize, column: &'static str| {
+        fields[index]
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| CsvError::InvalidField {
+                line: line_number,
+                column,
+                value: fields[index].clone(),
+            })
+    };
+
+    let states = number(1, "states")?;
+    if states != fields[0].split('_').count() {
+        return Err(CsvError::InvalidField {
+            line: line_number,
+            column: "states",
+            value: fields[1].clone(),
+        });
+    }
+
+    let outcome = RunOutcome {
+        steps: number(2, "steps")?,
+        ones: number(3, "ones")?,
+        space: number(4, "space")?,
+        halt_state: State::Halt,
+        writes_of: [0; 2],
+    };
+
+    Ok((fields[0].clone(), outcome))
+}
+
+/// Quotes the field if it contains a comma, a quote or a line break.
+fn quote(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Splits a row into its fields, unquoting them.
+/// Returns `None` if a quoted field is not terminated or is followed by
+/// something else than a comma.
+fn split_fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        let mut field = String::new();
+
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next()? {
+                    '"' if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    '"' => break,
+                    c => field.push(c),
+                }
+            }
+            if !matches!(chars.peek(), None | Some(&',')) {
+                return None;
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == ',' {
+                    break;
+                }
+                field.push(c);
+                chars.next();
+            }
+        }
+
+        fields.push(field);
+        if chars.next().is_none() {
+            return Some(fields);
+        }
+    }
+}
+
+/// Error returned when reading CSV fails.
+#[derive(Debug)]
+pub enum CsvError {
+    /// Reading failed.
+    Io(io::Error),
+    /// The first line is not the expected header row.
+    MissingHeader,
+    /// A row does not have the expected number of fields or has an
+    /// unterminated quoted field.
+    InvalidRow {
+        /// Line number of the row, starting at 1.
+        line: usize,
+    },
+    /// A field is not a valid number or does not match the machine.
+    InvalidField {
+        /// Line number of the row, starting at 1.
+        line: usize,
+        /// Name of the column of the field.
+        column: &'static str,
+        /// Value of the field.
+        value: String,
+    },
+}
+
+impl Display for CsvError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::MissingHeader => write!(f, "missing header row `{}`", HEADER),
+            Self::InvalidRow { line } => write!(f, "invalid row on line {}", line),
+            Self::InvalidField {
+                line,
+                column,
+                value,
+            } => write!(f, "invalid {} `{}` on line {}", column, value, line),
+        }
+    }
+}
+
+impl Error for CsvError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turing_machine::TuringMachine;
+
+    fn records() -> Vec<(String, RunOutcome)> {
+        ["1RB1LB_1LA1RZ", "1RB1RZ_0RC1RB_1LC1LA"]
+            .iter()
+            .map(|text| {
+                let outcome = match text.split('_').count() {
+                    2 => TuringMachine::<2>::from_standard_text(text)
+                        .unwrap()
+                        .run(100),
+                    _ => TuringMachine::<3>::from_standard_text(text)
+                        .unwrap()
+                        .run(100),
+                };
+
+                // Write counts are not stored
+                let outcome = RunOutcome {
+                    writes_of: [0; 2],
+                    ..outcome.unwrap()
+                };
+
+                (text.to_string(), outcome)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn write_csv_format() {
+        let mut output = Vec::new();
+        write_csv(&records()[..1], &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "machine,states,steps,ones,space\n1RB1LB_1LA1RZ,2,6,4,4\n"
+        );
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let mut output = Vec::new();
+        write_csv(&records(), &mut output).unwrap();
+
+        assert_eq!(read_csv(output.as_slice()).unwrap(), records());
+    }
+
+    #[test]
+    fn csv_quoting_round_trip() {
+        let records = vec![(
+            "1RB,\"x\"".to_string(),
+            RunOutcome {
+                steps: 1,
+                ones: 1,
+                space: 1,
+                halt_state: State::Halt,
+                writes_of: [0, 0],
+            },
+        )];
+        let mut output = Vec::new();
+        write_csv(&records, &mut output).unwrap();
+
+        assert!(String::from_utf8(output.clone())
+            .unwrap()
+            .contains("\"1RB,\"\"x\"\"\",1,1,1,1"));
+        assert_eq!(read_csv(output.as_slice()).unwrap(), records);
+    }
+
+    #[test]
+    fn read_csv_missing_header() {
+        assert!(matches!(
+            read_csv(&b"1RB1LB_1LA1RZ,2,6,4,4\n"[..]),
+            Err(CsvError::MissingHeader)
+        ));
+        assert!(matches!(read_csv(&b""[..]), Err(CsvError::MissingHeader)));
+    }
+
+    #[test]
+    fn read_csv_malformed_fields() {
+        let csv = "machine,states,steps,ones,space\n1RB1LB_1LA1RZ,2,six,4,4\n";
+        match read_csv(csv.as_bytes()) {
+            Err(CsvError::InvalidField {
+                line,
+                column,
+                value,
+            }) => {
+                assert_eq!((line, column, value.as_str()), (2, "steps", "six"));
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let csv = "machine,states,steps,ones,space\n\n1RB1LB_1LA1RZ,2,6,-4,4\n";
+        assert!(matches!(
+            read_csv(csv.as_bytes()),
+            Err(CsvError::InvalidField {
+                line: 3,
+                column: "ones",
+                ..
+            })
+        ));
+
+        let csv = "machine,states,steps,ones,space\n1RB1LB_1LA1RZ,3,6,4,4\n";
+        assert!(matches!(
+            read_csv(csv.as_bytes()),
+            Err(CsvError::InvalidField {
+                column: "states",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn read_csv_malformed_rows() {
+        for row in &[
+            "1RB1LB_1LA1RZ,2,6,4",
+            "\"1RB1LB_1LA1RZ,2,6,4,4",
+            "\"1RB\"x,2,6,4,4",
+        ] {
+            let csv = format!("machine,states,steps,ones,space\n{}\n", row);
+
+            assert!(matches!(
+                read_csv(csv.as_bytes()),
+                Err(CsvError::InvalidRow { line: 2 })
+            ));
+        }
+    }
+}
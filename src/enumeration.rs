@@ -0,0 +1,361 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::search::suggested_step_budget;
+use crate::transition::{
+    non_halting_states, Action, Direction, PartialTransition, State, Transition,
+};
+#[cfg(feature = "rayon")]
+use crate::turing_machine::RunOutcome;
+use crate::turing_machine::{PartialTuringMachine, TuringMachine, UndefinedTransition};
+
+/// Returns an iterator over every `N`-state binary-alphabet Turing machine.
+///
+/// Each of the `2 * N` actions writes one of the 2 symbols, moves in one of the
+/// 2 directions and goes to one of the `N` states or halts so there are
+/// `(4 * (N + 1)) ^ (2 * N)` machines. They are generated lazily.
+///
+/// # Panics
+/// Panics if `N == 0` or `N > State::MAX_STATES`.
+pub fn enumerate_machines<const N: usize>() -> impl Iterator<Item = TuringMachine<N>> {
+    assert!(N >= 1 && N <= State::MAX_STATES);
+
+    Machines {
+        digits: Some([[0; 2]; N]),
+        fixed: 0,
+    }
+}
+
+/// Filters every `N`-state machine in parallel.
+///
+/// Each machine is run on the blank input for a maximum number of steps and
+/// is kept when `predicate` holds for it and the outcome of its run, `None`
+/// standing for a machine which did not halt.
+///
+/// The search space is split by the action of state A on 0 into
+/// `4 * (N + 1)` chunks which are decoded and run on the `rayon` thread pool.
+/// The collected machines are always the same as when filtering
+/// [`enumerate_machines`] serially but their order is unspecified.
+///
+/// # Panics
+/// Panics if `N == 0` or `N > State::MAX_STATES`.
+#[cfg(feature = "rayon")]
+#[allow(clippy::cast_possible_truncation)]
+pub fn enumerate_and_filter<const N: usize, F>(
+    max_steps: usize,
+    predicate: F,
+) -> Vec<TuringMachine<N>>
+where
+    F: Fn(&TuringMachine<N>, Option<RunOutcome>) -> bool + Sync,
+{
+    assert!(N >= 1 && N <= State::MAX_STATES);
+
+    (0..(4 * (N + 1)) as u8)
+        .into_par_iter()
+        .flat_map_iter(|first| {
+            let mut digits = [[0; 2]; N];
+            digits[0][0] = first;
+
+            Machines {
+                digits: Some(digits),
+                fixed: 1,
+            }
+            .filter(|machine| predicate(machine, machine.run(max_steps)))
+        })
+        .collect()
+}
+
+/// Iterator over every `N`-state Turing machine.
+struct Machines<const N: usize> {
+    /// Encoded actions of the next machine to yield or `None` once every
+    /// machine has been yielded.
+    digits: Option<[[u8; 2]; N]>,
+    /// Number of leading encoded actions which are never incremented.
+    fixed: usize,
+}
+
+impl<const N: usize> Iterator for Machines<N> {
+    type Item = TuringMachine<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let digits = self.digits?;
+
+        let machine = TuringMachine::new(
+            digits
+                .iter()
+                .map(|&[on_0, on_1]| Transition::new(decode_action(on_0), decode_action(on_1)))
+                .collect::<Vec<Transition>>()
+                .try_into()
+                .unwrap(),
+        );
+
+        self.digits = increment(digits, self.fixed);
+
+        Some(machine)
+    }
+}
+
+/// Decodes an action from its index among the `4 * (N + 1)` possible actions.
+#[inline]
+fn decode_action(digit: u8) -> Action {
+    Action::new(
+        digit & 1,
+        Direction::from(digit >> 1 & 1),
+        State::from(digit >> 2),
+    )
+}
+
+/// Returns the encoded actions of the next machine or `None` if `digits` encodes
+/// the last one, the first `fixed` actions being left untouched.
+#[allow(clippy::cast_possible_truncation)]
+fn increment<const N: usize>(mut digits: [[u8; 2]; N], fixed: usize) -> Option<[[u8; 2]; N]> {
+    let base = (4 * (N + 1)) as u8;
+
+    for digit in digits
+        .iter_mut()
+        .flat_map(|t| t.iter_mut())
+        .rev()
+        .take(2 * N - fixed)
+    {
+        *digit += 1;
+        if *digit < base {
+            return Some(digits);
+        }
+        *digit = 0;
+    }
+
+    None
+}
+
+/// Returns an iterator over the `N`-state Turing machines in tree normal form,
+/// running each of them for at most [`suggested_step_budget`] steps.
+///
+/// See [`enumerate_tnf_with`] for the machines yielded.
+///
+/// # Panics
+/// Panics if `N == 0` or `N > State::MAX_STATES`.
+///
+/// [`State::MAX_STATES`]: crate::transition::State::MAX_STATES
+#[inline]
+pub fn enumerate_tnf<const N: usize>() -> impl Iterator<Item = TuringMachine<N>> {
+    enumerate_tnf_with(suggested_step_budget(N))
+}
+
+/// Returns an iterator over the `N`-state Turing machines in tree normal form,
+/// running each of them for at most `max_steps` steps.
+///
+/// Machines are grown as in the bbchallenge tree normal form. The root writes a
+/// 1 and moves right to state B on the blank symbol in state A, which loses no
+/// generality up to symmetry, every other transition being undefined. Each
+/// machine is run on the blank tape and the first undefined transition it
+/// reaches is defined with every possible action, giving its children. A new
+/// state is only introduced when all the lower states are already used, which
+/// rules out machines only differing by a relabeling of their states, and
+/// transitions never reached stay undefined, which rules out machines only
+/// differing by unreachable transitions.
+///
+/// Every node of the tree is a machine in tree normal form and is yielded
+/// once, undefined transitions being halting as with
+/// [`PartialTuringMachine::into_complete`]. The nodes reaching an undefined
+/// transition within `max_steps` steps are the halting machines, halting on
+/// that transition, and the other ones are the leaves of the tree.
+///
+/// # Panics
+/// Panics if `N == 0` or `N > State::MAX_STATES`.
+///
+/// [`State::MAX_STATES`]: crate::transition::State::MAX_STATES
+pub fn enumerate_tnf_with<const N: usize>(
+    max_steps: usize,
+) -> impl Iterator<Item = TuringMachine<N>> {
+    let mut root = PartialTuringMachine::new([PartialTransition::default(); N]);
+    // A single state machine only halts if it halts at once
+    let next_state = if N == 1 { State::Halt } else { State::B };
+    root.add_transition(State::A, 0, Action::new(1, Direction::Right, next_state));
+
+    TreeNormalForm {
+        max_steps,
+        stack: vec![root],
+    }
+}
+
+/// Depth-first iterator over the `N`-state Turing machines in tree normal form.
+struct TreeNormalForm<const N: usize> {
+    /// Maximum number of steps a machine is run for.
+    max_steps: usize,
+    /// Partial machines left to explore.
+    stack: Vec<PartialTuringMachine<N>>,
+}
+
+impl<const N: usize> Iterator for TreeNormalForm<N> {
+    type Item = TuringMachine<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let machine = self.stack.pop()?;
+
+        if let Err(UndefinedTransition { state, symbol, .. }) = machine.run(self.max_steps) {
+            let state_count = (machine.highest_used_state().index() + 1).min(N);
+
+            for next_state in non_halting_states(state_count).rev() {
+                for &direction in &[Direction::Right, Direction::Left] {
+                    for &written in &[1, 0] {
+                        let mut child = machine.clone();
+                        child.add_transition(
+                            state,
+                            symbol,
+                            Action::new(written, direction, next_state),
+                        );
+                        self.stack.push(child);
+                    }
+                }
+            }
+        }
+
+        Some(machine.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tape::Tape;
+    use alloc::collections::BTreeSet;
+    use alloc::string::{String, ToString};
+
+    #[test]
+    fn enumerate_machines_count() {
+        assert_eq!(enumerate_machines::<2>().count(), 12usize.pow(4));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn enumerate_and_filter_bb2() {
+        let machines = enumerate_and_filter::<2, _>(10, |_, outcome| {
+            outcome.map_or(false, |outcome| outcome.ones >= 4)
+        });
+        let serial = enumerate_machines::<2>()
+            .filter(|tm| tm.run(10).map_or(false, |outcome| outcome.ones >= 4))
+            .count();
+
+        assert_eq!(machines.len(), serial);
+        assert!(machines
+            .iter()
+            .any(|tm| tm.to_standard_text() == "1RB1LB_1LA1RZ"));
+    }
+
+    #[test]
+    fn enumerate_machines_order() {
+        let mut machines = enumerate_machines::<2>();
+
+        assert_eq!(machines.next().unwrap().to_standard_text(), "0LZ0LZ_0LZ0LZ");
+        assert_eq!(machines.next().unwrap().to_standard_text(), "0LZ0LZ_0LZ1LZ");
+        assert_eq!(
+            enumerate_machines::<2>().last().unwrap().to_standard_text(),
+            "1RB1RB_1RB1RB"
+        );
+    }
+
+    #[test]
+    fn enumerate_machines_contains_bb2_champion() {
+        assert!(enumerate_machines::<2>().any(|tm| tm.to_standard_text() == "1RB1LB_1LA1RZ"));
+    }
+
+    /// Reduces a machine to its node in tree normal form, keeping the
+    /// transitions it reaches within `max_steps` steps and relabeling its
+    /// states in the order they are first reached.
+    /// Returns `None` if the machine does not start like the root.
+    fn tree_normal_form<const N: usize>(
+        machine: &TuringMachine<N>,
+        max_steps: usize,
+    ) -> Option<TuringMachine<N>> {
+        let (written, direction, next_state) = machine.transition(State::A).get_action_of(0);
+        if (written, direction) != (1, Direction::Right)
+            || next_state.is_halting()
+            || next_state == State::A
+        {
+            return None;
+        }
+
+        let mut labels = [None; N];
+        labels[0] = Some(State::A);
+        let mut used = State::A as u8;
+        let mut partial = PartialTuringMachine::new([PartialTransition::default(); N]);
+        let mut reached = [[false; 2]; N];
+        let mut tape = Tape::new();
+        let mut state = State::A;
+
+        for _ in 0..max_steps {
+            let symbol = tape.read();
+            let (written, direction, next_state) = machine.transition(state).get_action_of(symbol);
+            if next_state.is_halting() {
+                break;
+            }
+
+            if !reached[state.index() - 1][usize::from(symbol)] {
+                reached[state.index() - 1][usize::from(symbol)] = true;
+                let target = *labels[next_state.index() - 1].get_or_insert_with(|| {
+                    used += 1;
+                    State::from(used)
+                });
+                partial.add_transition(
+                    labels[state.index() - 1].unwrap(),
+                    symbol,
+                    Action::new(written, direction, target),
+                );
+            }
+
+            tape.write(written);
+            tape.move_head(direction);
+            state = next_state;
+        }
+
+        Some(partial.into())
+    }
+
+    #[test]
+    fn enumerate_tnf_count() {
+        let machines = enumerate_tnf::<2>()
+            .map(|tm| tm.to_standard_text())
+            .collect::<Vec<String>>();
+        let reduced = enumerate_machines::<2>()
+            .filter_map(|tm| tree_normal_form(&tm, suggested_step_budget(2)))
+            .map(|tm| tm.to_standard_text())
+            .collect::<BTreeSet<String>>();
+
+        // Machines reduced to their node by brute force are exactly the nodes
+        assert_eq!(machines.len(), 121);
+        assert_eq!(
+            machines.iter().cloned().collect::<BTreeSet<String>>(),
+            reduced
+        );
+
+        // The budget is exact for 2 states so longer runs give the same tree
+        assert_eq!(enumerate_tnf_with::<2>(100).count(), 121);
+    }
+
+    #[test]
+    fn enumerate_tnf_first_machines() {
+        let mut machines = enumerate_tnf::<2>();
+
+        assert_eq!(machines.next().unwrap().to_standard_text(), "1RB1RZ_0RZ1RZ");
+        assert_eq!(machines.next().unwrap().to_standard_text(), "1RB1RZ_0LA1RZ");
+        assert_eq!(
+            enumerate_tnf::<1>()
+                .map(|tm| tm.to_standard_text())
+                .collect::<Vec<String>>(),
+            vec!["1RZ1RZ"]
+        );
+    }
+
+    #[test]
+    fn enumerate_tnf_contains_bb2_champion() {
+        let machines = enumerate_tnf::<2>()
+            .map(|tm| tm.to_standard_text())
+            .collect::<Vec<String>>();
+
+        assert!(machines.contains(&"1RB1LB_1LA1RZ".to_string()));
+        assert!(!machines.contains(&"1RA1LA_1LB1RZ".to_string()));
+    }
+}
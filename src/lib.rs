@@ -1,5 +1,9 @@
 //! Crate for generating busy beaver candidates
+//!
+//! The crate is `no_std` compatible, only requiring `alloc`, when its default
+//! `std` feature is disabled.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(
     missing_docs,
     rust_2018_idioms,
@@ -10,9 +14,24 @@
 )]
 #![allow(clippy::inline_always)]
 
+extern crate alloc;
+
 pub mod turing_machine;
 
-/// Tape for binary alphabet Turing machine
+/// CSV import and export of busy beaver records
+#[cfg(feature = "std")]
+pub mod csv;
+
+/// Exhaustive enumeration of Turing machines
+pub mod enumeration;
+
+/// Quadruple form of Turing machines
+pub mod quadruple;
+
+/// Helpers for busy beaver searches
+pub mod search;
+
+/// Tapes for Turing machines
 pub mod tape;
 
 pub mod transition;
@@ -0,0 +1,154 @@
+use alloc::vec::Vec;
+
+use crate::tape::Tape;
+use crate::transition::{non_halting_states, Direction};
+use crate::turing_machine::TuringMachine;
+
+/// Operation performed by a quadruple, either writing a symbol or moving the
+/// head.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum QuadrupleAction {
+    /// Writes the symbol at the head position.
+    Write(u8),
+    /// Moves the head one step in the direction.
+    Move(Direction),
+}
+
+/// Quadruple form of an `N`-state binary-alphabet Turing machine, each
+/// transition either writing a symbol or moving the head.
+///
+/// A quintuple action writing a symbol, moving the head and going to a state
+/// is split into a quadruple writing the symbol and going to an intermediate
+/// state, then a quadruple moving the head whatever the symbol read and going
+/// to the target state. Each of the `2 * N` actions gets its own intermediate
+/// state so the quadruple form has `3 * N` states and runs twice as many steps
+/// as the original machine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuadrupleMachine<const N: usize> {
+    /// Quadruples on 0 and on 1 of each state, `None` standing for the
+    /// halting state. States `0..N` are the states of the original machine
+    /// and the following ones the intermediate states.
+    quadruples: Vec<[(QuadrupleAction, Option<usize>); 2]>,
+}
+
+impl<const N: usize> QuadrupleMachine<N> {
+    /// Returns the number of states of the machine, that is `3 * N`.
+    #[inline]
+    #[must_use]
+    pub fn states(&self) -> usize {
+        self.quadruples.len()
+    }
+
+    /// Returns the action performed in the given state when reading the given
+    /// symbol and the next state, `None` standing for the halting state.
+    ///
+    /// # Panics
+    /// Panics if the state is not lower than [`QuadrupleMachine::states`].
+    #[inline]
+    #[must_use]
+    pub fn quadruple(&self, state: usize, symbol: u8) -> (QuadrupleAction, Option<usize>) {
+        self.quadruples[state][usize::from(symbol)]
+    }
+
+    /// Runs the machine on the blank input for a maximum number of quadruple
+    /// steps, starting from state 0.
+    /// Returns `Some(productivity)` if the machine did halt and `None`
+    /// otherwise.
+    #[must_use]
+    pub fn run(&self, max_steps: usize) -> Option<usize> {
+        let mut current_state = Some(0);
+        let mut tape = Tape::new();
+
+        let mut i = 0;
+        while let Some(state) = current_state {
+            if i == max_steps {
+                return None;
+            }
+
+            let (action, next_state) = self.quadruple(state, tape.read());
+            match action {
+                QuadrupleAction::Write(symbol) => tape.write(symbol),
+                QuadrupleAction::Move(direction) => tape.move_head(direction),
+            }
+            current_state = next_state;
+            i += 1;
+        }
+
+        Some(tape.count_ones())
+    }
+}
+
+impl<const N: usize> From<&TuringMachine<N>> for QuadrupleMachine<N> {
+    fn from(machine: &TuringMachine<N>) -> Self {
+        // Intermediate state between writing and moving for each action
+        let intermediate = |index: usize, symbol: u8| N + 2 * index + usize::from(symbol);
+        let mut quadruples = Vec::with_capacity(3 * N);
+
+        for (index, state) in non_halting_states(N).enumerate() {
+            let write = |symbol: u8| {
+                let (written, _, _) = machine.transition(state).get_action_of(symbol);
+                (
+                    QuadrupleAction::Write(written),
+                    Some(intermediate(index, symbol)),
+                )
+            };
+
+            quadruples.push([write(0), write(1)]);
+        }
+
+        for state in non_halting_states(N) {
+            for symbol in 0..2 {
+                let (_, direction, next_state) = machine.transition(state).get_action_of(symbol);
+                let target = (!next_state.is_halting()).then(|| next_state as usize - 1);
+
+                quadruples.push([(QuadrupleAction::Move(direction), target); 2]);
+            }
+        }
+
+        Self { quadruples }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enumeration::enumerate_machines;
+
+    #[test]
+    fn quadruple_form_layout() {
+        let tm = TuringMachine::<2>::from_standard_text("1RB1LB_1LA1RZ").unwrap();
+        let quadruples = tm.to_quadruple_form();
+
+        assert_eq!(quadruples.states(), 6);
+        assert_eq!(
+            quadruples.quadruple(1, 1),
+            (QuadrupleAction::Write(1), Some(5))
+        );
+        assert_eq!(
+            quadruples.quadruple(5, 0),
+            (QuadrupleAction::Move(Direction::Right), None)
+        );
+        assert_eq!(
+            quadruples.quadruple(2, 1),
+            (QuadrupleAction::Move(Direction::Right), Some(1))
+        );
+    }
+
+    #[test]
+    fn quadruple_form_same_productivity() {
+        let tm = TuringMachine::<3>::from_standard_text("1RB1RZ_0RC1RB_1LC1LA").unwrap();
+        let outcome = tm.run(100).unwrap();
+
+        assert_eq!(tm.to_quadruple_form().run(200), Some(outcome.ones));
+        assert_eq!(tm.to_quadruple_form().run(2 * outcome.steps - 1), None);
+
+        for tm in enumerate_machines::<2>() {
+            assert_eq!(
+                tm.to_quadruple_form().run(200),
+                tm.run(100).map(|outcome| outcome.ones),
+                "{}",
+                tm
+            );
+        }
+    }
+}
@@ -0,0 +1,591 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::enumeration::enumerate_tnf_with;
+use crate::turing_machine::{RunOutcome, TuringMachine};
+
+/// Best machines seen during a busy beaver search.
+///
+/// Candidates are fed with [`BusyBeaverRecord::consider`] and the record keeps
+/// the machine writing the most ones and the machine running the most steps.
+/// On ties the first machine seen is kept.
+#[derive(Debug, Clone, Default)]
+pub struct BusyBeaverRecord<const N: usize> {
+    /// Machine with the highest number of ones and its outcome.
+    best_by_ones: Option<(TuringMachine<N>, RunOutcome)>,
+    /// Machine with the highest number of steps and its outcome.
+    best_by_steps: Option<(TuringMachine<N>, RunOutcome)>,
+}
+
+impl<const N: usize> BusyBeaverRecord<N> {
+    /// Creates an empty record.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            best_by_ones: None,
+            best_by_steps: None,
+        }
+    }
+
+    /// Considers a halting machine and its outcome, keeping it if it beats the
+    /// current champions.
+    /// Returns whether the machine became a champion.
+    pub fn consider(&mut self, machine: TuringMachine<N>, outcome: RunOutcome) -> bool {
+        let beats_ones = self
+            .best_by_ones
+            .as_ref()
+            .map_or(true, |(_, best)| outcome.ones > best.ones);
+        let beats_steps = self
+            .best_by_steps
+            .as_ref()
+            .map_or(true, |(_, best)| outcome.steps > best.steps);
+
+        match (beats_ones, beats_steps) {
+            (true, true) => {
+                self.best_by_ones = Some((machine.clone(), outcome));
+                self.best_by_steps = Some((machine, outcome));
+            }
+            (true, false) => self.best_by_ones = Some((machine, outcome)),
+            (false, true) => self.best_by_steps = Some((machine, outcome)),
+            (false, false) => return false,
+        }
+
+        true
+    }
+
+    /// Returns the machine with the highest number of ones and its outcome.
+    #[inline]
+    #[must_use]
+    pub fn best_by_ones(&self) -> Option<(&TuringMachine<N>, RunOutcome)> {
+        self.best_by_ones
+            .as_ref()
+            .map(|(machine, outcome)| (machine, *outcome))
+    }
+
+    /// Returns the machine with the highest number of steps and its outcome.
+    #[inline]
+    #[must_use]
+    pub fn best_by_steps(&self) -> Option<(&TuringMachine<N>, RunOutcome)> {
+        self.best_by_steps
+            .as_ref()
+            .map(|(machine, outcome)| (machine, *outcome))
+    }
+}
+
+/// Returns a default step budget for searches over `n`-state machines.
+///
+/// Up to 5 states the budget is the maximum number of steps of a halting
+/// machine (1, 6, 21, 107 and 47 176 870) so that every machine still running
+/// afterwards never halts. Such bounds are unknown for more states and the
+/// 5-state budget is returned.
+#[must_use]
+pub const fn suggested_step_budget(n: usize) -> usize {
+    match n {
+        0 | 1 => 1,
+        2 => 6,
+        3 => 21,
+        4 => 107,
+        _ => 47_176_870,
+    }
+}
+
+/// Searches the `N`-state machines in tree normal form for busy beavers,
+/// running each of them for at most `max_steps` steps.
+///
+/// When `max_steps` is `None` the budget is given by
+/// [`suggested_step_budget`].
+///
+/// # Panics
+/// Panics if `N == 0` or `N > State::MAX_STATES`.
+///
+/// [`State::MAX_STATES`]: crate::transition::State::MAX_STATES
+#[must_use]
+pub fn busy_beaver_search<const N: usize>(max_steps: Option<usize>) -> BusyBeaverRecord<N> {
+    let max_steps = max_steps.unwrap_or_else(|| suggested_step_budget(N));
+    let mut record = BusyBeaverRecord::new();
+
+    for machine in enumerate_tnf_with::<N>(max_steps) {
+        if let Some(outcome) = machine.run(max_steps) {
+            record.consider(machine, outcome);
+        }
+    }
+
+    record
+}
+
+/// Parameters of a busy beaver search run by [`search_with_progress`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SearchConfig {
+    /// Maximum number of steps each machine is run for, given by
+    /// [`suggested_step_budget`] when `None`.
+    pub max_steps: Option<usize>,
+    /// Number of machines examined between two progress reports, 10 000 by
+    /// default.
+    pub report_interval: usize,
+}
+
+impl Default for SearchConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_steps: None,
+            report_interval: 10_000,
+        }
+    }
+}
+
+/// Progress of a busy beaver search reported by [`search_with_progress`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of machines examined so far.
+    pub examined: usize,
+    /// Number of times a machine became a champion so far.
+    pub champions: usize,
+    /// Time elapsed since the search started.
+    pub elapsed: Duration,
+}
+
+/// Searches the `N`-state machines in tree normal form for busy beavers like
+/// [`busy_beaver_search`], calling `report` every time
+/// `config.report_interval` more machines have been examined.
+///
+/// # Panics
+/// Panics if `N == 0` or `N > State::MAX_STATES` or if the report interval is
+/// zero.
+///
+/// [`State::MAX_STATES`]: crate::transition::State::MAX_STATES
+#[cfg(feature = "std")]
+pub fn search_with_progress<const N: usize>(
+    config: SearchConfig,
+    mut report: impl FnMut(&Progress),
+) -> BusyBeaverRecord<N> {
+    assert!(
+        config.report_interval > 0,
+        "the report interval must not be zero"
+    );
+
+    let max_steps = config.max_steps.unwrap_or_else(|| suggested_step_budget(N));
+    let start = Instant::now();
+    let mut record = BusyBeaverRecord::new();
+    let mut champions = 0;
+
+    for (index, machine) in enumerate_tnf_with::<N>(max_steps).enumerate() {
+        if let Some(outcome) = machine.run(max_steps) {
+            if record.consider(machine, outcome) {
+                champions += 1;
+            }
+        }
+
+        let examined = index + 1;
+        if examined % config.report_interval == 0 {
+            report(&Progress {
+                examined,
+                champions,
+                elapsed: start.elapsed(),
+            });
+        }
+    }
+
+    record
+}
+
+/// Returns the highest number of ones and the highest number of steps reached
+/// by the given machines which halt within `max_steps` steps.
+///
+/// Machines which do not halt within the budget are skipped. The bounds are
+/// `(0, 0)` if no machine halts.
+pub fn best_bounds<const N: usize, I: IntoIterator<Item = TuringMachine<N>>>(
+    machines: I,
+    max_steps: usize,
+) -> (usize, usize) {
+    machines
+        .into_iter()
+        .filter_map(|machine| machine.run(max_steps))
+        .fold((0, 0), |(ones, steps), outcome| {
+            (ones.max(outcome.ones), steps.max(outcome.steps))
+        })
+}
+
+/// Returns the highest number of ones written by the given machines which
+/// halt within `max_steps` steps along with every machine writing that many
+/// ones, in the order they were given.
+///
+/// Only the machines tying the current record are kept, the list being
+/// cleared whenever the record is beaten. Machines which do not halt within
+/// the budget are skipped and the list is empty if no machine halts.
+pub fn all_champions_by_ones<const N: usize, I: IntoIterator<Item = TuringMachine<N>>>(
+    machines: I,
+    max_steps: usize,
+) -> (usize, Vec<TuringMachine<N>>) {
+    let mut record = 0;
+    let mut champions = Vec::new();
+
+    for machine in machines {
+        if let Some(outcome) = machine.run(max_steps) {
+            if outcome.ones > record {
+                record = outcome.ones;
+                champions.clear();
+            }
+            if outcome.ones == record {
+                champions.push(machine);
+            }
+        }
+    }
+
+    (record, champions)
+}
+
+/// Returns the number of machines halting within `max_steps` steps for each
+/// number of ones they write.
+///
+/// Machines which do not halt within the budget are skipped.
+pub fn ones_histogram<const N: usize, I: IntoIterator<Item = TuringMachine<N>>>(
+    machines: I,
+    max_steps: usize,
+) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+
+    for outcome in machines
+        .into_iter()
+        .filter_map(|machine| machine.run(max_steps))
+    {
+        *histogram.entry(outcome.ones).or_insert(0) += 1;
+    }
+
+    histogram
+}
+
+/// Runs every machine on the blank input for a maximum number of steps and
+/// returns their outcomes in the same order, `None` standing for the machines
+/// which did not halt.
+///
+/// With the `rayon` feature the machines are run in parallel.
+#[must_use]
+pub fn run_batch<const N: usize>(
+    machines: &[TuringMachine<N>],
+    max_steps: usize,
+) -> Vec<Option<RunOutcome>> {
+    #[cfg(feature = "rayon")]
+    let machines = machines.par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let machines = machines.iter();
+
+    machines.map(|machine| machine.run(max_steps)).collect()
+}
+
+/// Extension trait for iterators over Turing machines.
+pub trait MachineIterExt<const N: usize>: Iterator<Item = TuringMachine<N>> + Sized {
+    /// Runs every machine on the blank input for a maximum number of steps and
+    /// yields only the machines which halt, paired with their outcome.
+    ///
+    /// ```
+    /// use busy_beaver::enumeration::enumerate_machines;
+    /// use busy_beaver::search::MachineIterExt;
+    ///
+    /// let (_, outcome) = enumerate_machines::<2>()
+    ///     .halting(20)
+    ///     .max_by_key(|(_, outcome)| outcome.ones)
+    ///     .unwrap();
+    /// assert_eq!(outcome.ones, 4);
+    /// ```
+    #[inline]
+    fn halting(self, max_steps: usize) -> Halting<Self, N> {
+        Halting {
+            machines: self,
+            max_steps,
+        }
+    }
+}
+
+impl<I: Iterator<Item = TuringMachine<N>>, const N: usize> MachineIterExt<N> for I {}
+
+/// Iterator over the halting machines of another iterator and their outcome.
+///
+/// This `struct` is created by [`MachineIterExt::halting`].
+#[derive(Debug, Clone)]
+pub struct Halting<I, const N: usize> {
+    /// Machines to run.
+    machines: I,
+    /// Maximum number of steps of each run.
+    max_steps: usize,
+}
+
+impl<I: Iterator<Item = TuringMachine<N>>, const N: usize> Iterator for Halting<I, N> {
+    type Item = (TuringMachine<N>, RunOutcome);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let max_steps = self.max_steps;
+
+        self.machines
+            .find_map(|machine| machine.run(max_steps).map(|outcome| (machine, outcome)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enumeration::enumerate_machines;
+    use crate::transition::State;
+
+    fn consider_all<const N: usize>(record: &mut BusyBeaverRecord<N>, machines: &[&str]) {
+        for text in machines {
+            let machine = TuringMachine::from_standard_text(text).unwrap();
+            let outcome = machine.run(1000).unwrap();
+            record.consider(machine, outcome);
+        }
+    }
+
+    #[test]
+    fn empty_record() {
+        let record = BusyBeaverRecord::<2>::new();
+
+        assert!(record.best_by_ones().is_none());
+        assert!(record.best_by_steps().is_none());
+    }
+
+    #[test]
+    fn record_keeps_champions() {
+        let mut record = BusyBeaverRecord::<3>::new();
+        consider_all(
+            &mut record,
+            &[
+                "1RZ1RZ_1RZ1RZ_1RZ1RZ",
+                "1RB1RZ_0RC1RB_1LC1LA",
+                "1RB1RZ_1LB0RC_1LC1LA",
+                "1RB1LB_1LA1RZ_1RZ1RZ",
+            ],
+        );
+
+        let (machine, outcome) = record.best_by_ones().unwrap();
+        assert_eq!(machine.to_standard_text(), "1RB1RZ_0RC1RB_1LC1LA");
+        assert_eq!(outcome.ones, 6);
+
+        let (machine, outcome) = record.best_by_steps().unwrap();
+        assert_eq!(machine.to_standard_text(), "1RB1RZ_1LB0RC_1LC1LA");
+        assert_eq!(outcome.steps, 21);
+    }
+
+    #[test]
+    fn record_keeps_first_on_ties() {
+        let mut record = BusyBeaverRecord::<2>::new();
+        consider_all(&mut record, &["1RB1LB_1LA1RZ", "1LB1RB_1RA1LZ"]);
+
+        assert_eq!(
+            record.best_by_ones().unwrap().0.to_standard_text(),
+            "1RB1LB_1LA1RZ"
+        );
+        assert_eq!(
+            record.best_by_steps().unwrap().0.to_standard_text(),
+            "1RB1LB_1LA1RZ"
+        );
+    }
+
+    #[test]
+    fn run_batch_preserves_order() {
+        let machines = ["1RA1RZ_1RZ1RZ", "1RB1LB_1LA1RZ", "1RZ1RZ_1RZ1RZ"]
+            .iter()
+            .map(|text| TuringMachine::<2>::from_standard_text(text).unwrap())
+            .collect::<Vec<TuringMachine<2>>>();
+
+        let outcomes = run_batch(&machines, 100);
+
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[0], None);
+        assert_eq!(
+            outcomes[1],
+            Some(RunOutcome {
+                steps: 6,
+                ones: 4,
+                space: 4,
+                halt_state: State::Halt,
+                writes_of: [0, 6],
+            })
+        );
+        assert_eq!(outcomes[2].map(|outcome| outcome.steps), Some(1));
+    }
+
+    #[test]
+    fn run_batch_matches_run() {
+        let machines = enumerate_machines::<2>().take(2000).collect::<Vec<_>>();
+        let outcomes = run_batch(&machines, 50);
+
+        assert_eq!(outcomes.len(), machines.len());
+        for (machine, outcome) in machines.iter().zip(outcomes) {
+            assert_eq!(machine.run(50), outcome);
+        }
+    }
+
+    #[test]
+    fn suggested_step_budget_values() {
+        assert_eq!(suggested_step_budget(2), 6);
+        assert_eq!(suggested_step_budget(3), 21);
+        assert_eq!(suggested_step_budget(4), 107);
+        assert!(suggested_step_budget(6) >= suggested_step_budget(5));
+    }
+
+    #[test]
+    fn busy_beaver_search_default_budget() {
+        let record = busy_beaver_search::<2>(None);
+
+        let (machine, outcome) = record.best_by_ones().unwrap();
+        assert_eq!(outcome.ones, 4);
+        assert_eq!(outcome.steps, 6);
+        assert_eq!(machine.run(suggested_step_budget(2)), Some(outcome));
+        assert_eq!(record.best_by_steps().unwrap().1.steps, 6);
+    }
+
+    #[test]
+    fn busy_beaver_search_bb1() {
+        // Undefined transitions of machines in tree normal form write back the
+        // symbol read so the halting step writes no 1
+        let record = busy_beaver_search::<1>(None);
+        assert_eq!(record.best_by_steps().unwrap().1.steps, 1);
+
+        assert_eq!(best_bounds(enumerate_machines::<1>(), 100), (1, 1));
+        assert_eq!(enumerate_machines::<1>().count(), 64);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn search_with_progress_reports() {
+        let config = SearchConfig {
+            max_steps: Some(100),
+            report_interval: 50,
+        };
+        let mut reports = Vec::new();
+        let record = search_with_progress::<2>(config, |progress| reports.push(*progress));
+
+        // 121 machines in tree normal form are examined
+        assert_eq!(
+            reports
+                .iter()
+                .map(|progress| progress.examined)
+                .collect::<Vec<_>>(),
+            [50, 100]
+        );
+        assert!(reports.windows(2).all(
+            |pair| pair[0].champions <= pair[1].champions && pair[0].elapsed <= pair[1].elapsed
+        ));
+        assert!(reports[0].champions > 0);
+
+        assert_eq!(record.best_by_ones().unwrap().1.ones, 4);
+        assert_eq!(record.best_by_steps().unwrap().1.steps, 6);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "the report interval must not be zero")]
+    fn search_with_progress_zero_interval() {
+        let config = SearchConfig {
+            report_interval: 0,
+            ..SearchConfig::default()
+        };
+        let _ = search_with_progress::<2>(config, |_| {});
+    }
+
+    #[test]
+    fn consider_tells_new_champions() {
+        let mut record = BusyBeaverRecord::<2>::new();
+        let bb2 = TuringMachine::from_standard_text("1RB1LB_1LA1RZ").unwrap();
+        let outcome = bb2.run(100).unwrap();
+
+        assert!(record.consider(bb2.clone(), outcome));
+        assert!(!record.consider(bb2, outcome));
+    }
+
+    #[test]
+    fn all_champions_by_ones_bb2() {
+        let (ones, champions) = all_champions_by_ones(enumerate_machines::<2>(), 100);
+
+        assert_eq!(ones, 4);
+        assert_eq!(
+            champions.len(),
+            ones_histogram(enumerate_machines::<2>(), 100)[&4]
+        );
+        assert!(champions
+            .iter()
+            .all(|tm| tm.run(100).map(|outcome| outcome.ones) == Some(4)));
+
+        let bb2 = TuringMachine::from_standard_text("1RB1LB_1LA1RZ").unwrap();
+        assert!(champions.contains(&bb2));
+        assert!(champions.contains(&bb2.mirror()));
+    }
+
+    #[test]
+    fn all_champions_by_ones_none_halting() {
+        let spinner = TuringMachine::<1>::from_standard_text("0RA0RA").unwrap();
+
+        assert_eq!(all_champions_by_ones(vec![spinner], 100), (0, Vec::new()));
+        assert_eq!(
+            all_champions_by_ones(core::iter::empty::<TuringMachine<2>>(), 100),
+            (0, Vec::new())
+        );
+    }
+
+    #[test]
+    fn busy_beaver_search_bb3() {
+        let record = busy_beaver_search::<3>(None);
+
+        assert_eq!(record.best_by_ones().unwrap().1.ones, 6);
+        assert_eq!(record.best_by_steps().unwrap().1.steps, 21);
+    }
+
+    #[test]
+    fn halting_filters_non_halters() {
+        let machines = ["1RA1RZ_1RZ1RZ", "1RB1LB_1LA1RZ", "1RB0LA_1LA1RB"]
+            .iter()
+            .map(|text| TuringMachine::<2>::from_standard_text(text).unwrap());
+
+        let halting = machines.halting(100).collect::<Vec<_>>();
+
+        assert_eq!(halting.len(), 1);
+        assert_eq!(halting[0].0.to_standard_text(), "1RB1LB_1LA1RZ");
+        assert_eq!(halting[0].1.steps, 6);
+    }
+
+    #[test]
+    fn halting_finds_bb2() {
+        let (_, outcome) = enumerate_machines::<2>()
+            .halting(20)
+            .max_by_key(|(_, outcome)| outcome.ones)
+            .unwrap();
+
+        assert_eq!(outcome.ones, 4);
+        assert!(enumerate_machines::<2>()
+            .halting(20)
+            .all(|(machine, outcome)| machine.run(20) == Some(outcome)));
+    }
+
+    #[test]
+    fn best_bounds_bb2() {
+        assert_eq!(best_bounds(enumerate_machines::<2>(), 100), (4, 6));
+    }
+
+    #[test]
+    fn ones_histogram_bb2() {
+        let histogram = ones_histogram(enumerate_machines::<2>(), 100);
+
+        assert_eq!(histogram.keys().next_back(), Some(&4));
+        assert_eq!(histogram[&4], 4);
+        assert_eq!(
+            histogram.values().sum::<usize>(),
+            enumerate_machines::<2>()
+                .filter(|machine| machine.run(100).is_some())
+                .count()
+        );
+        assert!(ones_histogram(Vec::<TuringMachine<2>>::new(), 100).is_empty());
+    }
+
+    #[test]
+    fn best_bounds_empty() {
+        assert_eq!(best_bounds(Vec::<TuringMachine<2>>::new(), 100), (0, 0));
+    }
+}
@@ -1,26 +1,432 @@
 use crate::transition::Direction;
-use std::collections::VecDeque;
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display};
+use core::hash::{Hash, Hasher};
+use core::ops::{BitAnd, BitOr, Not, Range, Shl, Shr};
+#[cfg(feature = "std")]
+use std::error::Error;
 
-/// A tape of binary-alphabet symbols.
-#[derive(Debug)]
-pub struct Tape {
+/// Base of the polynomial hash of the symbols around the head.
+const FINGERPRINT_BASE: u64 = 0x0000_0100_0000_01b3;
+
+/// Multiplicative inverse of `FINGERPRINT_BASE` modulo 2^64.
+const FINGERPRINT_BASE_INVERSE: u64 = inverse(FINGERPRINT_BASE);
+
+/// Panic message of a head moving left of position 0 on a one-way tape.
+const FELL_OFF_TAPE: &str = "the head fell off the left end of a one-way tape";
+
+/// Computes the multiplicative inverse of an odd number modulo 2^64 with
+/// Newton's method, each iteration doubling the number of correct bits.
+const fn inverse(x: u64) -> u64 {
+    let mut inverse = x;
+    let mut i = 0;
+    while i < 6 {
+        inverse = inverse.wrapping_mul(2u64.wrapping_sub(x.wrapping_mul(inverse)));
+        i += 1;
+    }
+
+    inverse
+}
+
+/// Polynomial hash of the symbols within a fixed radius of the head, updated
+/// incrementally as the tape is written and the head moves.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct WindowFingerprint {
+    /// Number of cells on each side of the head covered by the hash
+    radius: usize,
+    /// Hash of the window, the leftmost symbol having the highest power
+    hash: u64,
+    /// Power of the base weighting the head symbol
+    head_power: u64,
+    /// Power of the base weighting the leftmost symbol
+    edge_power: u64,
+}
+
+impl WindowFingerprint {
+    /// Creates the fingerprint of a window of the given radius from its hash.
+    fn new(radius: usize, hash: u64) -> Self {
+        let power = |exponent: usize| {
+            (0..exponent).fold(1u64, |power, _| power.wrapping_mul(FINGERPRINT_BASE))
+        };
+
+        Self {
+            radius,
+            hash,
+            head_power: power(radius),
+            edge_power: power(2 * radius),
+        }
+    }
+
+    /// Updates the hash after the head symbol was replaced.
+    #[inline(always)]
+    fn replace_head(&mut self, old: u8, new: u8) {
+        self.hash = self
+            .hash
+            .wrapping_add(u64::from(new).wrapping_mul(self.head_power))
+            .wrapping_sub(u64::from(old).wrapping_mul(self.head_power));
+    }
+
+    /// Updates the hash after the head moved one step in the given direction,
+    /// `leaving` being the symbol which left the window and `entering` the
+    /// symbol which entered it.
+    #[inline(always)]
+    fn shift(&mut self, direction: Direction, leaving: u8, entering: u8) {
+        self.hash = match direction {
+            Direction::Right => self
+                .hash
+                .wrapping_sub(u64::from(leaving).wrapping_mul(self.edge_power))
+                .wrapping_mul(FINGERPRINT_BASE)
+                .wrapping_add(u64::from(entering)),
+            Direction::Left => self
+                .hash
+                .wrapping_sub(u64::from(leaving))
+                .wrapping_mul(FINGERPRINT_BASE_INVERSE)
+                .wrapping_add(u64::from(entering).wrapping_mul(self.edge_power)),
+        };
+    }
+}
+
+/// Unsigned integer type of the cells of a [`PackedTape`], each bit of a cell
+/// holding a symbol.
+///
+/// Narrower cells allocate the tape in smaller blocks while wider cells
+/// reallocate less often, which changes the cache behavior of the simulation.
+pub trait Cell:
+    Copy
+    + Eq
+    + Hash
+    + Debug
+    + Not<Output = Self>
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    /// Number of symbols held by a cell, a power of two.
+    const BITS: u32;
+    /// Cell holding only zeros.
+    const ZERO: Self;
+    /// Cell holding a one at bit 0 only.
+    const ONE: Self;
+
+    /// Returns the number of ones in the cell.
+    fn count_ones(self) -> u32;
+    /// Returns the number of leading zeros of the cell.
+    fn leading_zeros(self) -> u32;
+    /// Returns the number of trailing zeros of the cell.
+    fn trailing_zeros(self) -> u32;
+}
+
+macro_rules! impl_cell {
+    ($($cell:ty),*) => {
+        $(
+            impl Cell for $cell {
+                const BITS: u32 = <$cell>::BITS;
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                #[inline(always)]
+                fn count_ones(self) -> u32 {
+                    self.count_ones()
+                }
+
+                #[inline(always)]
+                fn leading_zeros(self) -> u32 {
+                    self.leading_zeros()
+                }
+
+                #[inline(always)]
+                fn trailing_zeros(self) -> u32 {
+                    self.trailing_zeros()
+                }
+            }
+        )*
+    };
+}
+
+impl_cell!(u8, u16, u32, u64, u128);
+
+/// A tape of binary-alphabet symbols packed into `u64` cells.
+pub type Tape = PackedTape<u64>;
+
+/// A tape of binary-alphabet symbols packed into cells of type `C`.
+///
+/// The blank symbol is 0 unless the tape is created with
+/// [`PackedTape::new_with_blank`]. Cells are initialized to the blank symbol
+/// when they are allocated so reading and writing do not depend on it.
+///
+/// Tapes compare equal when they hold the same symbols with the same head
+/// position, regardless of the positions visited to get there and of the
+/// blank cells allocated around the symbols.
+#[derive(Debug, Clone, Eq)]
+pub struct PackedTape<C: Cell> {
     /// Bit vector representing the tape
-    cells: VecDeque<u64>,
+    cells: VecDeque<C>,
     /// Position of the head on the tape
     head: isize,
     /// Range of the allocated tape representing the tape
     range: (isize, isize),
+    /// Symbol of the cells never written
+    blank: u8,
+    /// Incrementally maintained hash of the window around the head
+    fingerprint: Option<WindowFingerprint>,
+    /// Leftmost and rightmost positions visited by the head
+    excursion: (isize, isize),
+    /// Number of times each symbol was written
+    writes: [usize; 2],
+    /// Whether the tape ends on the left of position 0
+    one_way: bool,
 }
 
-impl Tape {
+impl<C: Cell> PackedTape<C> {
+    /// Number of symbols held by a cell.
+    #[allow(clippy::cast_possible_wrap)]
+    const WIDTH: isize = C::BITS as isize;
+
     /// Creates a new tape with two cells and head at position 0.
     #[inline]
     #[must_use]
     pub fn new() -> Self {
+        Self::with_capacity(2)
+    }
+
+    /// Creates a new tape with at least the given number of cells allocated
+    /// symmetrically around the head at position 0.
+    ///
+    /// The number of cells is rounded up to an even number of at least two.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn with_capacity(cells: usize) -> Self {
+        let half = (cells / 2 + cells % 2).max(1);
+
         Self {
-            cells: vec![0, 0].into_iter().collect(),
+            cells: vec![C::ZERO; 2 * half].into_iter().collect(),
+            head: 0,
+            range: (
+                -Self::WIDTH * half as isize,
+                Self::WIDTH * half as isize - 1,
+            ),
+            blank: 0,
+            fingerprint: None,
+            excursion: (0, 0),
+            writes: [0; 2],
+            one_way: false,
+        }
+    }
+
+    /// Creates a new tape holding the given symbols from position 0 onwards,
+    /// with head at the given position.
+    ///
+    /// The allocated cells cover both the symbols and the head, other
+    /// positions being blank.
+    ///
+    /// # Panics
+    /// Panics in `debug` mode if a symbol is not 0 or 1.
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    pub fn from_symbols(symbols: &[u8], head: isize) -> Self {
+        let leftmost = head.min(0);
+        let rightmost = head.max(symbols.len() as isize - 1).max(0);
+        let left_cells = ((-leftmost + Self::WIDTH - 1) / Self::WIDTH).max(1);
+        let right_cells = (rightmost + Self::WIDTH) / Self::WIDTH;
+
+        let mut tape = Self {
+            cells: vec![C::ZERO; (left_cells + right_cells) as usize]
+                .into_iter()
+                .collect(),
             head: 0,
-            range: (-64, 63),
+            range: (-Self::WIDTH * left_cells, Self::WIDTH * right_cells - 1),
+            blank: 0,
+            fingerprint: None,
+            excursion: (head, head),
+            writes: [0; 2],
+            one_way: false,
+        };
+
+        for (position, &symbol) in symbols.iter().enumerate() {
+            tape.head = position as isize;
+            tape.write(symbol);
+        }
+        tape.head = head;
+        tape.writes = [0; 2];
+
+        tape
+    }
+
+    /// Creates a new tape with head at position 0 which is infinite only to the
+    /// right, position 0 being its leftmost cell.
+    ///
+    /// Moving the head left of position 0 panics, the head may be moved with
+    /// [`Tape::move_head_one_way`] to detect when it would fall off the left
+    /// end of the tape instead.
+    #[inline]
+    #[must_use]
+    pub fn new_one_way() -> Self {
+        Self {
+            range: (0, 2 * Self::WIDTH - 1),
+            one_way: true,
+            ..Self::new()
+        }
+    }
+
+    /// Checks if the tape is infinite only to the right.
+    #[inline]
+    #[must_use]
+    pub const fn is_one_way(&self) -> bool {
+        self.one_way
+    }
+
+    /// Creates a new tape with two cells and head at position 0 whose never
+    /// written cells hold the given blank symbol.
+    ///
+    /// # Panics
+    /// Panics in `debug` mode if blank is not 0 or 1.
+    #[inline]
+    #[must_use]
+    pub fn new_with_blank(blank: u8) -> Self {
+        debug_assert!(blank == 0 || blank == 1);
+
+        let mut tape = Self::new();
+        tape.blank = blank;
+        let blank_cell = tape.blank_cell();
+        tape.cells.iter_mut().for_each(|cell| *cell = blank_cell);
+
+        tape
+    }
+
+    /// Resets the tape to its initial state with two blank cells and head at
+    /// position 0, keeping its blank symbol and its allocated memory.
+    #[inline]
+    pub fn reset(&mut self) {
+        let blank_cell = self.blank_cell();
+
+        self.cells.truncate(2);
+        self.cells.iter_mut().for_each(|cell| *cell = blank_cell);
+        self.cells.resize(2, blank_cell);
+        self.head = 0;
+        self.range = if self.one_way {
+            (0, 2 * Self::WIDTH - 1)
+        } else {
+            (-Self::WIDTH, Self::WIDTH - 1)
+        };
+        self.excursion = (0, 0);
+        self.writes = [0; 2];
+        self.refresh_fingerprint();
+    }
+
+    /// Frees the allocated cells holding only blank symbols at both ends of
+    /// the tape, keeping the cell under the head.
+    ///
+    /// Symbols, head position and excursion are unchanged so the tape reads
+    /// the same everywhere after compaction.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn compact(&mut self) {
+        let blank_cell = self.blank_cell();
+        let (head_cell, _) = self.get_cell_bit_index(self.head);
+
+        let first = self
+            .cells
+            .iter()
+            .position(|&cell| cell != blank_cell)
+            .map_or(head_cell, |first| first.min(head_cell));
+        let last = self
+            .cells
+            .iter()
+            .rposition(|&cell| cell != blank_cell)
+            .map_or(head_cell, |last| last.max(head_cell));
+
+        self.cells.truncate(last + 1);
+        self.cells.drain(..first);
+        self.range = (
+            self.range.0 + Self::WIDTH * first as isize,
+            self.range.0 + Self::WIDTH * (last + 1) as isize - 1,
+        );
+    }
+
+    /// Returns the mirror image of the tape about the head, the symbol `k`
+    /// cells right of the head ending up `k` cells left of it and conversely.
+    ///
+    /// The head position, blank symbol and write counts are kept while the
+    /// excursion is reflected too. The mirror image of a one-way tape is a
+    /// two-way tape as its end is no longer at position 0 in general.
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn mirror(&self) -> Self {
+        let mut mirror = Self::new_with_blank(self.blank);
+
+        let (start, cells) = self.written_cells();
+        if !cells.is_empty() {
+            let end = start + Self::WIDTH * cells.len() as isize - 1;
+
+            // Reflected positions are written from left to right
+            mirror.move_head_to(2 * self.head - end);
+            for position in (start..=end).rev() {
+                mirror.write(self.symbol_at(position));
+                mirror.move_head(Direction::Right);
+            }
+        }
+
+        mirror.move_head_to(self.head);
+        mirror.excursion = (
+            2 * self.head - self.excursion.1,
+            2 * self.head - self.excursion.0,
+        );
+        mirror.writes = self.writes;
+        if let Some(fingerprint) = self.fingerprint {
+            mirror.track_window(fingerprint.radius);
+        }
+
+        mirror
+    }
+
+    /// Moves the head to the given position.
+    fn move_head_to(&mut self, position: isize) {
+        let direction = if position < self.head {
+            Direction::Left
+        } else {
+            Direction::Right
+        };
+
+        self.move_head_by(direction, (position - self.head).unsigned_abs());
+    }
+
+    /// Returns the position of the first symbol of the cells holding
+    /// non-blank symbols along with the range of their indices, the range
+    /// being empty if the tape is blank.
+    ///
+    /// Cells always start at a multiple of the cell width so tapes holding the
+    /// same symbols have the same such cells whatever their allocated range.
+    #[allow(clippy::cast_possible_wrap)]
+    fn written_cells(&self) -> (isize, Range<usize>) {
+        let blank_cell = self.blank_cell();
+
+        self.cells
+            .iter()
+            .position(|&cell| cell != blank_cell)
+            .map_or((0, 0..0), |first| {
+                let last = self
+                    .cells
+                    .iter()
+                    .rposition(|&cell| cell != blank_cell)
+                    .unwrap();
+
+                (self.range.0 + Self::WIDTH * first as isize, first..last + 1)
+            })
+    }
+
+    /// Returns a cell holding only blank symbols.
+    #[inline(always)]
+    #[must_use]
+    fn blank_cell(&self) -> C {
+        if self.blank == 1 {
+            !C::ZERO
+        } else {
+            C::ZERO
         }
     }
 
@@ -31,7 +437,22 @@ impl Tape {
     pub fn read(&self) -> u8 {
         let (cell_index, bit_index) = self.get_cell_bit_index(self.head);
 
-        ((self.cells[cell_index] >> bit_index) & 1) as u8
+        Self::bit(self.cells[cell_index], bit_index)
+    }
+
+    /// Reads the symbol `offset` cells away from the head without moving it,
+    /// positions outside of the allocated range being blank.
+    #[inline]
+    #[must_use]
+    pub fn peek(&self, offset: isize) -> u8 {
+        self.symbol_at(self.head + offset)
+    }
+
+    /// Returns the symbol held at the given bit of a cell.
+    #[inline(always)]
+    #[must_use]
+    fn bit(cell: C, bit_index: u8) -> u8 {
+        u8::from((cell >> u32::from(bit_index)) & C::ONE != C::ZERO)
     }
 
     /// Writes the given binary symbol at the head position.
@@ -42,30 +463,105 @@ impl Tape {
     pub fn write(&mut self, symbol: u8) {
         debug_assert!(symbol == 0 || symbol == 1);
 
+        if let Some(mut fingerprint) = self.fingerprint {
+            fingerprint.replace_head(self.read(), symbol);
+            self.fingerprint = Some(fingerprint);
+        }
+
+        self.writes[usize::from(symbol)] += 1;
+
         let (cell_index, bit_index) = self.get_cell_bit_index(self.head);
 
-        if symbol == 1 {
-            self.cells[cell_index] |= 1 << bit_index;
+        let cell = self.cells[cell_index];
+        let mask = C::ONE << u32::from(bit_index);
+
+        self.cells[cell_index] = if symbol == 1 {
+            cell | mask
         } else {
-            self.cells[cell_index] &= !(1 << bit_index);
-        }
+            cell & !mask
+        };
     }
 
     /// Moves the head one step in the given direction
     /// If the head moves out of the allocated cells range a new cell is allocated
+    ///
+    /// # Panics
+    /// Panics if the head moves left of position 0 on a one-way tape.
     #[inline]
     pub fn move_head(&mut self, direction: Direction) {
         if self.head == self.range.0 {
-            self.cells.push_front(0);
-            self.range.0 -= 64;
+            // The left end of a one-way tape is never allocated past
+            if self.one_way && self.head == 0 {
+                assert!(direction == Direction::Right, "{}", FELL_OFF_TAPE);
+            } else {
+                self.cells.push_front(self.blank_cell());
+                self.range.0 -= Self::WIDTH;
+            }
         }
 
         if self.head == self.range.1 {
-            self.cells.push_back(0);
-            self.range.1 += 64;
+            self.cells.push_back(self.blank_cell());
+            self.range.1 += Self::WIDTH;
+        }
+
+        self.head += isize::from(direction.delta());
+        self.excursion.0 = self.excursion.0.min(self.head);
+        self.excursion.1 = self.excursion.1.max(self.head);
+        self.shift_fingerprint(direction);
+    }
+
+    /// Moves the head one step in the given direction like
+    /// [`Tape::move_head`] unless the head would fall off the left end of a
+    /// one-way tape.
+    /// Returns `false` without moving the head in that case and `true`
+    /// otherwise.
+    #[inline]
+    pub fn move_head_one_way(&mut self, direction: Direction) -> bool {
+        if self.one_way && self.head == 0 && direction == Direction::Left {
+            return false;
+        }
+
+        self.move_head(direction);
+        true
+    }
+
+    /// Moves the head one step in the given direction like
+    /// [`Tape::move_head`] unless the tape would then hold more than
+    /// `max_cells` allocated cells.
+    ///
+    /// # Errors
+    /// Returns a `CellLimitExceeded` error without modifying the tape if the
+    /// move needs more than `max_cells` cells.
+    #[inline]
+    pub fn try_move_head(
+        &mut self,
+        direction: Direction,
+        max_cells: usize,
+    ) -> Result<(), CellLimitExceeded> {
+        let grows = self.head == self.range.0 || self.head == self.range.1;
+        if self.allocated_cells() + usize::from(grows) > max_cells {
+            return Err(CellLimitExceeded { max_cells });
         }
 
-        self.head += direction as isize * 2 - 1;
+        self.move_head(direction);
+        Ok(())
+    }
+
+    /// Updates the tracked fingerprint after the head moved one step in the
+    /// given direction.
+    #[inline]
+    #[allow(clippy::cast_possible_wrap)]
+    fn shift_fingerprint(&mut self, direction: Direction) {
+        if let Some(mut fingerprint) = self.fingerprint {
+            let radius = fingerprint.radius as isize;
+            let (leaving, entering) = match direction {
+                Direction::Right => (self.head - radius - 1, self.head + radius),
+                Direction::Left => (self.head + radius + 1, self.head - radius),
+            };
+
+            fingerprint.shift(direction, self.symbol_at(leaving), self.symbol_at(entering));
+            self.fingerprint = Some(fingerprint);
+        }
     }
 
     /// Computes the cell index and bit index of the given position on the tape.
@@ -79,127 +575,641 @@ impl Tape {
         debug_assert!(self.range.0 <= position && position <= self.range.1);
 
         let positive_position = (-self.range.0 + position) as usize;
-        (positive_position >> 6, (positive_position & 63) as u8)
+        (
+            positive_position >> C::BITS.trailing_zeros(),
+            (positive_position & (C::BITS as usize - 1)) as u8,
+        )
     }
 
-    /// Counts the number of ones written on the tape.
-    #[inline]
+    /// Returns the symbols within `radius` cells of the head, from left to right.
+    /// Cells outside of the allocated range are read as blank.
     #[must_use]
-    pub fn count_ones(&self) -> usize {
-        self.cells.iter().map(|c| c.count_ones() as usize).sum()
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn window(&self, radius: usize) -> Vec<u8> {
+        let radius = radius as isize;
+
+        (self.head - radius..=self.head + radius)
+            .map(|position| self.symbol_at(position))
+            .collect()
     }
-}
 
-impl Default for Tape {
-    #[inline(always)]
+    /// Counts the consecutive cells holding `symbol` starting at the head and
+    /// going in the given direction.
+    /// Returns `None` if the run is infinite, that is if it reaches the end of
+    /// the allocated range and `symbol` is the blank symbol.
+    ///
+    /// Whole cells are scanned at once using their leading or trailing zeros.
     #[must_use]
-    fn default() -> Self {
-        Self::new()
+    pub(crate) fn run_length(&self, symbol: u8, direction: Direction) -> Option<usize> {
+        let (mut cell_index, bit_index) = self.get_cell_bit_index(self.head);
+        // Ones of the mask mark the cells differing from the run symbol
+        let mask = |cell: C| if symbol == 1 { !cell } else { cell };
+        let bit_index = u32::from(bit_index);
+
+        let mut length = match direction {
+            Direction::Right => {
+                let run = (mask(self.cells[cell_index]) >> bit_index).trailing_zeros();
+                let run = run.min(C::BITS - bit_index) as usize;
+                if bit_index as usize + run < C::BITS as usize {
+                    return Some(run);
+                }
+                run
+            }
+            Direction::Left => {
+                let run =
+                    (mask(self.cells[cell_index]) << (C::BITS - 1 - bit_index)).leading_zeros();
+                let run = run.min(bit_index + 1) as usize;
+                if run <= bit_index as usize {
+                    return Some(run);
+                }
+                run
+            }
+        };
+
+        loop {
+            let next_cell = match direction {
+                Direction::Right => cell_index + 1,
+                Direction::Left => cell_index.wrapping_sub(1),
+            };
+            // Cells outside of the allocated range are blank forever
+            let cell = match self.cells.get(next_cell) {
+                Some(&cell) => mask(cell),
+                // A one-way tape ends at position 0
+                None if self.one_way && direction == Direction::Left => return Some(length),
+                None if symbol == self.blank => return None,
+                None => return Some(length),
+            };
+            let run = match direction {
+                Direction::Right => cell.trailing_zeros(),
+                Direction::Left => cell.leading_zeros(),
+            } as usize;
+
+            length += run;
+            if run < C::BITS as usize {
+                return Some(length);
+            }
+            cell_index = next_cell;
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Moves the head `count` cells in the given direction without writing,
+    /// allocating the same cells as `count` calls to `move_head` would.
+    ///
+    /// # Panics
+    /// Panics if the head moves left of position 0 on a one-way tape.
+    #[allow(clippy::cast_possible_wrap)]
+    pub(crate) fn move_head_by(&mut self, direction: Direction, count: usize) {
+        if count == 0 {
+            return;
+        }
+        if self.one_way && direction == Direction::Left {
+            assert!(count as isize <= self.head, "{}", FELL_OFF_TAPE);
+        }
 
-    #[test]
-    fn move_head_left() {
-        let mut t = Tape::new();
-        assert_eq!(t.head, 0);
+        match direction {
+            Direction::Right => {
+                if self.head == self.range.0 && !(self.one_way && self.head == 0) {
+                    self.cells.push_front(self.blank_cell());
+                    self.range.0 -= Self::WIDTH;
+                }
 
-        t.move_head(Direction::Left);
-        assert_eq!(t.head, -1);
+                self.head += count as isize;
+                while self.range.1 < self.head {
+                    self.cells.push_back(self.blank_cell());
+                    self.range.1 += Self::WIDTH;
+                }
+            }
+            Direction::Left => {
+                if self.head == self.range.1 {
+                    self.cells.push_back(self.blank_cell());
+                    self.range.1 += Self::WIDTH;
+                }
 
-        t.move_head(Direction::Left);
-        assert_eq!(t.head, -2);
+                self.head -= count as isize;
+                while self.range.0 > self.head {
+                    self.cells.push_front(self.blank_cell());
+                    self.range.0 -= Self::WIDTH;
+                }
+            }
+        }
+
+        self.excursion.0 = self.excursion.0.min(self.head);
+        self.excursion.1 = self.excursion.1.max(self.head);
+        self.refresh_fingerprint();
     }
 
-    #[test]
-    fn move_head_right() {
-        let mut t = Tape::new();
-        assert_eq!(t.head, 0);
+    /// Writes `symbol` and moves the head in the given direction `count` times.
+    ///
+    /// The head jumps to its destination at once and the written positions
+    /// are filled a whole cell at a time.
+    pub(crate) fn sweep(&mut self, symbol: u8, direction: Direction, count: usize) {
+        if count == 0 {
+            return;
+        }
 
-        t.move_head(Direction::Right);
-        assert_eq!(t.head, 1);
+        let start = self.head;
+        // Written positions lie between the head and its destination so they
+        // are allocated by the jump
+        self.move_head_by(direction, count);
+        let end = self.head - isize::from(direction.delta());
+        self.fill(start.min(end), start.max(end), symbol);
 
-        t.move_head(Direction::Right);
-        assert_eq!(t.head, 2);
+        self.writes[usize::from(symbol)] += count;
+        self.refresh_fingerprint();
     }
 
-    #[test]
-    fn move_head_both_directions() {
-        let mut t = Tape::new();
-        assert_eq!(t.head, 0);
+    /// Writes `symbol` at every allocated position from `first` to `last`,
+    /// masking the written bits of each cell.
+    fn fill(&mut self, first: isize, last: isize, symbol: u8) {
+        let (first_cell, first_bit) = self.get_cell_bit_index(first);
+        let (last_cell, last_bit) = self.get_cell_bit_index(last);
 
-        t.move_head(Direction::Right);
-        assert_eq!(t.head, 1);
-
-        t.move_head(Direction::Left);
-        assert_eq!(t.head, 0);
+        for cell_index in first_cell..=last_cell {
+            let low = if cell_index == first_cell {
+                u32::from(first_bit)
+            } else {
+                0
+            };
+            let high = if cell_index == last_cell {
+                u32::from(last_bit)
+            } else {
+                C::BITS - 1
+            };
+            // Ones from bit `low` to bit `high`
+            let mask = (!C::ZERO >> (C::BITS - 1 - (high - low))) << low;
 
-        t.move_head(Direction::Left);
-        assert_eq!(t.head, -1);
+            let cell = self.cells[cell_index];
+            self.cells[cell_index] = if symbol == 1 {
+                cell | mask
+            } else {
+                cell & !mask
+            };
+        }
+    }
 
-        t.move_head(Direction::Right);
-        assert_eq!(t.head, 0);
+    /// Moves the head `count` times in the given direction over cells holding
+    /// `symbol`, as if each of them was rewritten with it.
+    pub(crate) fn rewrite_by(&mut self, symbol: u8, direction: Direction, count: usize) {
+        self.writes[usize::from(symbol)] += count;
+        self.move_head_by(direction, count);
     }
 
-    #[test]
-    fn move_head_left_overflow() {
-        let mut t = Tape::new();
-        for _ in 0..64 {
-            t.move_head(Direction::Left);
-        }
+    /// Renders the symbols within `radius` cells of the head, the head cell
+    /// being surrounded by brackets (e.g. `01[1]00`).
+    /// The rendered cells are clamped to the allocated range.
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn render(&self, radius: usize) -> String {
+        let radius = radius.min(isize::MAX as usize) as isize;
+        let start = self.head.saturating_sub(radius).max(self.range.0);
+        let end = self.head.saturating_add(radius).min(self.range.1);
 
-        assert_eq!(t.head, -64);
-        assert_eq!(t.range, (-64, 63));
+        (start..=end)
+            .map(|position| {
+                let symbol = self.symbol_at(position);
+                if position == self.head {
+                    format!("[{}]", symbol)
+                } else {
+                    symbol.to_string()
+                }
+            })
+            .collect()
+    }
 
-        t.move_head(Direction::Left);
-        assert_eq!(t.head, -65);
-        assert_eq!(t.range, (-128, 63));
+    /// Starts tracking the fingerprint of the window of `radius` cells around
+    /// the head, replacing any previously tracked window.
+    ///
+    /// The fingerprint is then updated in constant time on every write and
+    /// head move so [`Tape::window_fingerprint`] is free for this radius.
+    pub fn track_window(&mut self, radius: usize) {
+        self.fingerprint = Some(WindowFingerprint::new(
+            radius,
+            self.compute_fingerprint(radius),
+        ));
     }
 
-    #[test]
-    fn move_head_right_overflow() {
-        let mut t = Tape::new();
-        for _ in 0..63 {
-            t.move_head(Direction::Right);
+    /// Returns a hash of the symbols within `radius` cells of the head, as
+    /// returned by [`Tape::window`].
+    ///
+    /// Equal windows have equal fingerprints so comparing fingerprints is a
+    /// cheap way to rule out equal windows. The fingerprint is maintained
+    /// incrementally for the radius given to [`Tape::track_window`] and
+    /// computed from scratch for other radii.
+    #[must_use]
+    pub fn window_fingerprint(&self, radius: usize) -> u64 {
+        match self.fingerprint {
+            Some(fingerprint) if fingerprint.radius == radius => fingerprint.hash,
+            _ => self.compute_fingerprint(radius),
         }
+    }
 
-        assert_eq!(t.head, 63);
-        assert_eq!(t.range, (-64, 63));
+    /// Computes the fingerprint of the window of `radius` cells around the head.
+    #[allow(clippy::cast_possible_wrap)]
+    fn compute_fingerprint(&self, radius: usize) -> u64 {
+        let radius = radius as isize;
 
-        t.move_head(Direction::Right);
-        assert_eq!(t.head, 64);
-        assert_eq!(t.range, (-64, 127));
+        (self.head - radius..=self.head + radius).fold(0, |hash, position| {
+            hash.wrapping_mul(FINGERPRINT_BASE)
+                .wrapping_add(u64::from(self.symbol_at(position)))
+        })
     }
 
-    #[test]
-    fn read() {
-        let t = Tape::new();
+    /// Recomputes the tracked fingerprint from scratch, if any.
+    fn refresh_fingerprint(&mut self) {
+        if let Some(fingerprint) = self.fingerprint {
+            self.fingerprint = Some(WindowFingerprint::new(
+                fingerprint.radius,
+                self.compute_fingerprint(fingerprint.radius),
+            ));
+        }
+    }
 
-        assert_eq!(t.read(), 0);
+    /// Returns the leftmost and rightmost positions ever visited by the head.
+    ///
+    /// Unlike the allocated range, which grows by whole cells, the
+    /// excursion is exact.
+    #[inline]
+    #[must_use]
+    pub const fn excursion(&self) -> (isize, isize) {
+        self.excursion
     }
 
-    #[test]
-    fn write_0() {
-        let mut t = Tape::new();
+    /// Returns how many times each symbol was written since the tape was
+    /// created or reset, indexed by symbol.
+    ///
+    /// Rewriting a cell with the symbol it already holds counts as a write.
+    #[inline]
+    #[must_use]
+    pub const fn writes_of(&self) -> [usize; 2] {
+        self.writes
+    }
 
-        t.write(0);
-        assert_eq!(t.read(), 0);
+    /// Returns the number of allocated cells, each one holding `C::BITS` symbols.
+    #[inline]
+    #[must_use]
+    pub(crate) fn allocated_cells(&self) -> usize {
+        self.cells.len()
     }
 
-    #[test]
-    fn write_1() {
-        let mut t = Tape::new();
+    /// Returns the position of the head on the tape.
+    #[inline]
+    #[must_use]
+    pub const fn head(&self) -> isize {
+        self.head
+    }
 
-        t.write(1);
-        assert_eq!(t.read(), 1);
+    /// Returns the leftmost and rightmost positions of the allocated cells.
+    ///
+    /// The range grows by whole cells of `C::BITS` symbols as the head moves
+    /// past its ends, see [`Tape::excursion`] for the exact visited positions.
+    #[inline]
+    #[must_use]
+    pub const fn allocated_range(&self) -> (isize, isize) {
+        self.range
     }
 
-    #[test]
-    fn cell_bit_index() {
-        let mut t = Tape::new();
+    /// Reads the symbol at the given position, positions outside of the
+    /// allocated range being blank.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    fn symbol_at(&self, position: isize) -> u8 {
+        if position < self.range.0 || position > self.range.1 {
+            return self.blank;
+        }
+
+        let (cell_index, bit_index) = self.get_cell_bit_index(position);
+
+        Self::bit(self.cells[cell_index], bit_index)
+    }
+
+    /// Returns an iterator over the symbols of the allocated range of the tape,
+    /// from left to right.
+    #[inline]
+    pub fn iter_cells(&self) -> impl Iterator<Item = u8> + '_ {
+        (self.range.0..=self.range.1).map(move |position| self.symbol_at(position))
+    }
+
+    /// Returns an iterator over the positions not holding the blank symbol
+    /// along with their symbol, from left to right.
+    #[inline]
+    pub fn iter_nonblank(&self) -> impl Iterator<Item = (isize, u8)> + '_ {
+        (self.range.0..=self.range.1)
+            .zip(self.iter_cells())
+            .filter(move |&(_, symbol)| symbol != self.blank)
+    }
+
+    /// Returns the run-length encoding of the allocated range of the tape as
+    /// `(symbol, count)` pairs from left to right.
+    ///
+    /// The blank runs at both ends are included up to the allocated range.
+    #[must_use]
+    pub fn run_length_encode(&self) -> Vec<(u8, usize)> {
+        let mut runs: Vec<(u8, usize)> = Vec::new();
+
+        for symbol in self.iter_cells() {
+            match runs.last_mut() {
+                Some((last, count)) if *last == symbol => *count += 1,
+                _ => runs.push((symbol, 1)),
+            }
+        }
+
+        runs
+    }
+
+    /// Counts the number of ones written on the tape.
+    ///
+    /// The count saturates at `usize::MAX`, which can only be reached on
+    /// targets whose `usize` is narrower than the number of symbols allocated,
+    /// e.g. a tape of more than 2^32 symbols on a 32-bit target.
+    #[inline]
+    #[must_use]
+    pub fn count_ones(&self) -> usize {
+        self.count_symbol(1)
+    }
+
+    /// Counts the number of cells of the allocated range holding `symbol`.
+    ///
+    /// Counting the blank symbol depends on how much of the tape is allocated.
+    /// Like [`Tape::count_ones`] the count saturates at `usize::MAX`.
+    #[inline]
+    #[must_use]
+    pub fn count_symbol(&self, symbol: u8) -> usize {
+        let ones = self.cells.iter().fold(0usize, |ones, c| {
+            ones.saturating_add(c.count_ones() as usize)
+        });
+
+        if symbol == 1 {
+            ones
+        } else {
+            (C::BITS as usize)
+                .saturating_mul(self.cells.len())
+                .saturating_sub(ones)
+        }
+    }
+}
+
+impl<C: Cell> PartialEq for PackedTape<C> {
+    fn eq(&self, other: &Self) -> bool {
+        let (position, cells) = self.written_cells();
+        let (other_position, other_cells) = other.written_cells();
+
+        self.head == other.head
+            && self.blank == other.blank
+            && self.one_way == other.one_way
+            && position == other_position
+            && cells.len() == other_cells.len()
+            && self.cells.range(cells).eq(other.cells.range(other_cells))
+    }
+}
+
+impl<C: Cell> Hash for PackedTape<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let (position, cells) = self.written_cells();
+
+        position.hash(state);
+        cells.len().hash(state);
+        self.cells.range(cells).for_each(|cell| cell.hash(state));
+        self.head.hash(state);
+        self.blank.hash(state);
+        self.one_way.hash(state);
+    }
+}
+
+impl<C: Cell> Default for PackedTape<C> {
+    #[inline(always)]
+    #[must_use]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Number of cells allocated at once when a `GenericTape` grows.
+const GENERIC_BLOCK_SIZE: isize = 64;
+
+/// A tape of symbols from an alphabet of `SYMBOLS` symbols.
+///
+/// Unlike `Tape` which packs binary symbols into bits, each symbol is stored in
+/// its own byte so `Tape` should be preferred for binary-alphabet machines.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GenericTape<const SYMBOLS: usize> {
+    /// Symbols of the tape
+    cells: VecDeque<u8>,
+    /// Position of the head on the tape
+    head: isize,
+    /// Range of the allocated tape representing the tape
+    range: (isize, isize),
+}
+
+impl<const SYMBOLS: usize> GenericTape<SYMBOLS> {
+    /// Creates a new blank tape with head at position 0.
+    ///
+    /// # Panics
+    /// Panics if `SYMBOLS` is not between 2 and 256.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        assert!((2..=256).contains(&SYMBOLS));
+
+        Self {
+            cells: vec![0; 2 * GENERIC_BLOCK_SIZE as usize]
+                .into_iter()
+                .collect(),
+            head: 0,
+            range: (-GENERIC_BLOCK_SIZE, GENERIC_BLOCK_SIZE - 1),
+        }
+    }
+
+    /// Reads the symbol on the tape at the head position.
+    #[inline]
+    #[must_use]
+    pub fn read(&self) -> u8 {
+        self.cells[self.get_cell_index(self.head)]
+    }
+
+    /// Writes the given symbol at the head position.
+    ///
+    /// # Panics
+    /// Panics in `debug` mode if symbol is not in the alphabet.
+    #[inline]
+    pub fn write(&mut self, symbol: u8) {
+        debug_assert!((symbol as usize) < SYMBOLS);
+
+        let cell_index = self.get_cell_index(self.head);
+        self.cells[cell_index] = symbol;
+    }
+
+    /// Moves the head one step in the given direction
+    /// If the head moves out of the allocated cells range a new block of cells
+    /// is allocated
+    #[inline]
+    pub fn move_head(&mut self, direction: Direction) {
+        if self.head == self.range.0 {
+            for _ in 0..GENERIC_BLOCK_SIZE {
+                self.cells.push_front(0);
+            }
+            self.range.0 -= GENERIC_BLOCK_SIZE;
+        }
+
+        if self.head == self.range.1 {
+            for _ in 0..GENERIC_BLOCK_SIZE {
+                self.cells.push_back(0);
+            }
+            self.range.1 += GENERIC_BLOCK_SIZE;
+        }
+
+        self.head += isize::from(direction.delta());
+    }
+
+    /// Counts the number of non-blank symbols written on the tape.
+    #[inline]
+    #[must_use]
+    pub fn count_nonblank(&self) -> usize {
+        self.cells.iter().filter(|&&symbol| symbol != 0).count()
+    }
+
+    /// Computes the cell index of the given position on the tape.
+    ///
+    /// # Panic
+    /// Panics in `debug` mode if index is out of range.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::cast_sign_loss)]
+    fn get_cell_index(&self, position: isize) -> usize {
+        debug_assert!(self.range.0 <= position && position <= self.range.1);
+
+        (position - self.range.0) as usize
+    }
+}
+
+impl<const SYMBOLS: usize> Default for GenericTape<SYMBOLS> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error returned when moving the head needs more cells than allowed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CellLimitExceeded {
+    /// Maximum number of cells the tape was allowed to allocate.
+    pub max_cells: usize,
+}
+
+impl Display for CellLimitExceeded {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tape would exceed {} cells", self.max_cells)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for CellLimitExceeded {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn move_head_left() {
+        let mut t = Tape::new();
+        assert_eq!(t.head, 0);
+
+        t.move_head(Direction::Left);
+        assert_eq!(t.head, -1);
+
+        t.move_head(Direction::Left);
+        assert_eq!(t.head, -2);
+    }
+
+    #[test]
+    fn move_head_right() {
+        let mut t = Tape::new();
+        assert_eq!(t.head, 0);
+
+        t.move_head(Direction::Right);
+        assert_eq!(t.head, 1);
+
+        t.move_head(Direction::Right);
+        assert_eq!(t.head, 2);
+    }
+
+    #[test]
+    fn move_head_both_directions() {
+        let mut t = Tape::new();
+        assert_eq!(t.head, 0);
+
+        t.move_head(Direction::Right);
+        assert_eq!(t.head, 1);
+
+        t.move_head(Direction::Left);
+        assert_eq!(t.head, 0);
+
+        t.move_head(Direction::Left);
+        assert_eq!(t.head, -1);
+
+        t.move_head(Direction::Right);
+        assert_eq!(t.head, 0);
+    }
+
+    #[test]
+    fn move_head_left_overflow() {
+        let mut t = Tape::new();
+        for _ in 0..64 {
+            t.move_head(Direction::Left);
+        }
+
+        assert_eq!(t.head, -64);
+        assert_eq!(t.range, (-64, 63));
+
+        t.move_head(Direction::Left);
+        assert_eq!(t.head, -65);
+        assert_eq!(t.range, (-128, 63));
+    }
+
+    #[test]
+    fn move_head_right_overflow() {
+        let mut t = Tape::new();
+        for _ in 0..63 {
+            t.move_head(Direction::Right);
+        }
+
+        assert_eq!(t.head, 63);
+        assert_eq!(t.range, (-64, 63));
+
+        t.move_head(Direction::Right);
+        assert_eq!(t.head, 64);
+        assert_eq!(t.range, (-64, 127));
+    }
+
+    #[test]
+    fn read() {
+        let t = Tape::new();
+
+        assert_eq!(t.read(), 0);
+    }
+
+    #[test]
+    fn write_0() {
+        let mut t = Tape::new();
+
+        t.write(0);
+        assert_eq!(t.read(), 0);
+    }
+
+    #[test]
+    fn write_1() {
+        let mut t = Tape::new();
+
+        t.write(1);
+        assert_eq!(t.read(), 1);
+    }
+
+    #[test]
+    fn cell_bit_index() {
+        let mut t = Tape::new();
 
         assert_eq!(t.get_cell_bit_index(0), (1, 0));
         assert_eq!(t.get_cell_bit_index(1), (1, 1));
@@ -210,4 +1220,862 @@ mod tests {
         assert_eq!(t.get_cell_bit_index(1), (2, 1));
         assert_eq!(t.get_cell_bit_index(-1), (1, 63));
     }
+
+    #[test]
+    fn cell_bit_index_widths() {
+        let mut t = PackedTape::<u8>::new();
+        assert_eq!(t.range, (-8, 7));
+        assert_eq!(t.get_cell_bit_index(7), (1, 7));
+        assert_eq!(t.get_cell_bit_index(-8), (0, 0));
+
+        for _ in 0..8 {
+            t.move_head(Direction::Right);
+        }
+        assert_eq!(t.range, (-8, 15));
+        assert_eq!(t.get_cell_bit_index(8), (2, 0));
+        t.write(1);
+
+        for _ in 0..17 {
+            t.move_head(Direction::Left);
+        }
+        assert_eq!(t.range, (-16, 15));
+        assert_eq!(t.get_cell_bit_index(-9), (0, 7));
+        assert_eq!(t.get_cell_bit_index(8), (3, 0));
+        assert_eq!(t.symbol_at(8), 1);
+
+        let mut t = PackedTape::<u32>::new();
+        assert_eq!(t.range, (-32, 31));
+        assert_eq!(t.get_cell_bit_index(-1), (0, 31));
+        assert_eq!(t.get_cell_bit_index(31), (1, 31));
+
+        t.move_head_by(Direction::Left, 40);
+        assert_eq!(t.range, (-64, 31));
+        assert_eq!(t.get_cell_bit_index(-40), (0, 24));
+        assert_eq!(t.get_cell_bit_index(-32), (1, 0));
+        assert_eq!(t.get_cell_bit_index(0), (2, 0));
+    }
+
+    #[test]
+    fn run_length_narrow_cells() {
+        let mut t = PackedTape::<u8>::new();
+        t.sweep(1, Direction::Right, 20);
+        t.head = 0;
+
+        assert_eq!(t.run_length(1, Direction::Right), Some(20));
+        assert_eq!(t.run_length(0, Direction::Left), Some(0));
+        t.head = -1;
+        assert_eq!(t.run_length(0, Direction::Left), None);
+        assert_eq!(t.count_ones(), 20);
+        assert_eq!(t.count_symbol(0), 8 * t.cells.len() - 20);
+    }
+
+    #[test]
+    fn count_ones_blank() {
+        let t = Tape::new();
+
+        assert_eq!(t.count_ones(), 0);
+    }
+
+    #[test]
+    fn count_ones_scattered() {
+        let mut t = Tape::new();
+
+        t.write(1);
+        for _ in 0..5 {
+            t.move_head(Direction::Right);
+        }
+        t.write(1);
+        t.write(1);
+        for _ in 0..70 {
+            t.move_head(Direction::Right);
+        }
+        t.write(1);
+        for _ in 0..150 {
+            t.move_head(Direction::Left);
+        }
+        t.write(1);
+        t.move_head(Direction::Left);
+        t.write(1);
+
+        assert_eq!(t.range, (-128, 127));
+        assert_eq!(t.count_ones(), 5);
+    }
+
+    #[test]
+    fn count_ones_overwrite() {
+        let mut t = Tape::new();
+
+        t.write(1);
+        t.move_head(Direction::Left);
+        t.write(1);
+        t.move_head(Direction::Right);
+        t.write(0);
+
+        assert_eq!(t.count_ones(), 1);
+    }
+
+    fn hash_of(tape: &Tape) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        tape.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn eq_ignores_allocation() {
+        let write_pattern = |tape: &mut Tape| {
+            tape.head = 0;
+            for &symbol in &[1, 0, 1, 1] {
+                tape.write(symbol);
+                tape.move_head(Direction::Right);
+            }
+            tape.head = 1;
+        };
+
+        let mut tape = Tape::new();
+        write_pattern(&mut tape);
+
+        let mut padded = Tape::with_capacity(8);
+        write_pattern(&mut padded);
+        assert_ne!(padded.range, tape.range);
+        assert_eq!(padded, tape);
+        assert_eq!(hash_of(&padded), hash_of(&tape));
+
+        let mut wandered = Tape::new();
+        wandered.move_head_by(Direction::Left, 200);
+        wandered.move_head_by(Direction::Right, 400);
+        write_pattern(&mut wandered);
+        assert_eq!(wandered, tape);
+        assert_eq!(hash_of(&wandered), hash_of(&tape));
+
+        wandered.compact();
+        assert_eq!(wandered, tape);
+
+        assert_eq!(Tape::new(), Tape::with_capacity(10));
+        assert_eq!(hash_of(&Tape::new()), hash_of(&Tape::with_capacity(10)));
+    }
+
+    #[test]
+    fn eq_compares_head_and_symbols() {
+        let mut tape = Tape::new();
+        tape.write(1);
+        let mut other = tape.clone();
+
+        other.head = 1;
+        assert_ne!(other, tape);
+
+        other.head = 0;
+        other.write(0);
+        assert_ne!(other, tape);
+
+        let mut shifted = Tape::new();
+        shifted.move_head_by(Direction::Right, 64);
+        shifted.write(1);
+        shifted.move_head_by(Direction::Left, 64);
+        assert_ne!(shifted, tape);
+        assert_ne!(Tape::new(), Tape::new_with_blank(1));
+    }
+
+    #[test]
+    fn compact() {
+        let mut tape = Tape::new();
+        for _ in 0..300 {
+            tape.move_head(Direction::Left);
+        }
+        for _ in 0..600 {
+            tape.move_head(Direction::Right);
+        }
+        for &position in &[-70, -3, 5, 100] {
+            tape.head = position;
+            tape.write(1);
+        }
+        tape.head = 10;
+        let symbols = (-400..400)
+            .map(|position| tape.symbol_at(position))
+            .collect::<Vec<_>>();
+        let allocated = tape.cells.len();
+
+        tape.compact();
+
+        assert!(tape.cells.len() < allocated);
+        assert_eq!(tape.cells.len(), 4);
+        assert_eq!(tape.range, (-128, 127));
+        assert_eq!(tape.head, 10);
+        assert_eq!(tape.read(), 0);
+        assert_eq!(tape.count_ones(), 4);
+        assert_eq!(
+            (-400..400)
+                .map(|position| tape.symbol_at(position))
+                .collect::<Vec<_>>(),
+            symbols
+        );
+
+        tape.head = 100;
+        assert_eq!(tape.read(), 1);
+        tape.move_head(Direction::Right);
+        tape.write(1);
+        assert_eq!(tape.count_ones(), 5);
+    }
+
+    #[test]
+    fn compact_blank_tape_keeps_head_cell() {
+        let mut tape = Tape::new();
+        for _ in 0..200 {
+            tape.move_head(Direction::Right);
+        }
+
+        tape.compact();
+
+        assert_eq!(tape.cells.len(), 1);
+        assert_eq!(tape.range, (192, 255));
+        assert_eq!(tape.read(), 0);
+        tape.move_head(Direction::Left);
+        assert_eq!(tape.read(), 0);
+    }
+
+    #[test]
+    fn peek() {
+        let mut tape = Tape::new();
+        for &symbol in &[1, 0, 1, 1] {
+            tape.write(symbol);
+            tape.move_head(Direction::Right);
+        }
+        tape.head = 1;
+
+        assert_eq!(tape.peek(-1), 1);
+        assert_eq!(tape.peek(0), tape.read());
+        assert_eq!(tape.peek(1), 1);
+        assert_eq!(tape.peek(2), 1);
+        assert_eq!(tape.peek(3), 0);
+        assert_eq!(tape.head, 1);
+
+        assert_eq!(tape.peek(-1000), 0);
+        assert_eq!(tape.peek(1000), 0);
+        assert_eq!(Tape::new_with_blank(1).peek(1000), 1);
+    }
+
+    #[test]
+    fn window() {
+        let mut t = Tape::new();
+
+        t.write(1);
+        t.move_head(Direction::Left);
+        t.move_head(Direction::Left);
+        t.write(1);
+        t.move_head(Direction::Right);
+
+        assert_eq!(t.window(0), vec![0]);
+        assert_eq!(t.window(2), vec![0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn window_outside_allocated_range() {
+        let mut t = Tape::new();
+        for _ in 0..63 {
+            t.move_head(Direction::Right);
+        }
+        t.write(1);
+
+        assert_eq!(t.window(2), vec![0, 0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn run_length() {
+        let mut t = Tape::new();
+        t.write(1);
+        t.move_head(Direction::Right);
+        t.write(1);
+        t.move_head(Direction::Left);
+
+        assert_eq!(t.run_length(1, Direction::Right), Some(2));
+        assert_eq!(t.run_length(1, Direction::Left), Some(1));
+        assert_eq!(t.run_length(0, Direction::Right), Some(0));
+
+        t.move_head(Direction::Left);
+        assert_eq!(t.run_length(0, Direction::Right), Some(1));
+        assert_eq!(t.run_length(0, Direction::Left), None);
+    }
+
+    #[test]
+    fn run_length_across_cells() {
+        let mut t = Tape::with_capacity(6);
+        t.sweep(1, Direction::Right, 150);
+        t.move_head(Direction::Left);
+
+        for _ in 0..150 {
+            assert_eq!(
+                t.run_length(1, Direction::Left),
+                Some((t.head + 1) as usize)
+            );
+            assert_eq!(
+                t.run_length(1, Direction::Right),
+                Some(150 - t.head as usize)
+            );
+            t.move_head(Direction::Left);
+        }
+
+        assert_eq!(t.run_length(1, Direction::Left), Some(0));
+        assert_eq!(t.run_length(0, Direction::Left), None);
+        assert_eq!(t.run_length(0, Direction::Right), Some(1));
+    }
+
+    #[test]
+    fn run_length_matches_symbol_scan() {
+        let mut t = Tape::with_capacity(4);
+        for i in 0..200 {
+            t.write(u8::from(i % 7 < 3 || i % 50 > 30));
+            t.move_head(Direction::Left);
+        }
+
+        for position in t.range.0..=t.range.1 {
+            t.head = position;
+            for &symbol in &[0, 1] {
+                for &direction in &[Direction::Left, Direction::Right] {
+                    let step = isize::from(direction.delta());
+                    let mut p = position;
+                    let mut expected = Some(0);
+                    while t.symbol_at(p) == symbol {
+                        if p < t.range.0 || p > t.range.1 {
+                            expected = None;
+                            break;
+                        }
+                        p += step;
+                        expected = expected.map(|length| length + 1);
+                    }
+
+                    assert_eq!(t.run_length(symbol, direction), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn move_head_by() {
+        for &direction in &[Direction::Left, Direction::Right] {
+            for &count in &[0, 1, 63, 64, 65, 200] {
+                let mut stepped = Tape::new();
+                let mut jumped = Tape::new();
+                stepped.sweep(0, !direction, 64);
+                jumped.sweep(0, !direction, 64);
+
+                for _ in 0..count {
+                    stepped.move_head(direction);
+                }
+                jumped.move_head_by(direction, count);
+
+                assert_eq!(jumped, stepped);
+            }
+        }
+    }
+
+    #[test]
+    fn sweep() {
+        let mut t = Tape::new();
+        t.sweep(1, Direction::Left, 100);
+
+        assert_eq!(t.head, -100);
+        assert_eq!(t.count_ones(), 100);
+        assert_eq!(t.run_length(1, Direction::Right), Some(0));
+
+        t.move_head(Direction::Right);
+        assert_eq!(t.run_length(1, Direction::Right), Some(100));
+    }
+
+    #[test]
+    fn sweep_matches_steps() {
+        for &direction in &[Direction::Left, Direction::Right] {
+            for &(start, count) in &[(0, 1), (3, 5), (-2, 8), (5, 16), (7, 40), (0, 100)] {
+                for &symbol in &[0, 1] {
+                    let mut stepped =
+                        PackedTape::<u8>::from_symbols(&[1, 0, 1, 1, 0, 1, 0, 1], start);
+                    stepped.track_window(3);
+                    let mut swept = stepped.clone();
+
+                    for _ in 0..count {
+                        stepped.write(symbol);
+                        stepped.move_head(direction);
+                    }
+                    swept.sweep(symbol, direction, count);
+
+                    assert_eq!(swept, stepped);
+                    assert_eq!(swept.range, stepped.range);
+                    assert_eq!(swept.excursion(), stepped.excursion());
+                    assert_eq!(swept.writes_of(), stepped.writes_of());
+                    assert_eq!(swept.window_fingerprint(3), stepped.window_fingerprint(3));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generic_tape_read_write() {
+        let mut t = GenericTape::<3>::new();
+        assert_eq!(t.read(), 0);
+
+        t.write(2);
+        assert_eq!(t.read(), 2);
+
+        t.write(1);
+        assert_eq!(t.read(), 1);
+    }
+
+    #[test]
+    fn generic_tape_reallocation() {
+        let mut t = GenericTape::<3>::new();
+        for i in 0..200 {
+            t.write(i % 3);
+            t.move_head(Direction::Left);
+        }
+
+        assert_eq!(t.head, -200);
+        assert_eq!(t.range, (-256, 63));
+        assert_eq!(t.read(), 0);
+
+        for i in (0..200).rev() {
+            t.move_head(Direction::Right);
+            assert_eq!(t.read(), i % 3);
+        }
+
+        for i in 0..100 {
+            t.write(2 - i % 3);
+            t.move_head(Direction::Right);
+        }
+        assert_eq!(t.range, (-256, 127));
+
+        for i in (0..100).rev() {
+            t.move_head(Direction::Left);
+            assert_eq!(t.read(), 2 - i % 3);
+        }
+        assert_eq!(t.count_nonblank(), 200);
+    }
+
+    #[test]
+    fn render() {
+        let mut t = Tape::new();
+        t.write(1);
+        t.move_head(Direction::Right);
+        t.move_head(Direction::Right);
+        t.write(1);
+        t.move_head(Direction::Left);
+
+        assert_eq!(t.render(0), "[0]");
+        assert_eq!(t.render(2), "01[0]10");
+    }
+
+    #[test]
+    fn render_clamps_to_allocated_range() {
+        let mut t = Tape::new();
+        t.write(1);
+
+        let rendered = t.render(1000);
+        assert_eq!(rendered.len(), 128 + 2);
+        assert!(rendered.starts_with('0'));
+        assert_eq!(rendered.find('['), Some(64));
+        assert_eq!(&rendered[64..67], "[1]");
+
+        assert_eq!(t.render(usize::MAX).len(), 128 + 2);
+    }
+
+    #[test]
+    fn with_capacity() {
+        let t = Tape::with_capacity(8);
+        assert_eq!(t.cells.len(), 8);
+        assert_eq!(t.range, (-256, 255));
+
+        let t = Tape::with_capacity(3);
+        assert_eq!(t.cells.len(), 4);
+        assert_eq!(t.range, (-128, 127));
+
+        let t = Tape::with_capacity(0);
+        assert_eq!(t.cells.len(), 2);
+        assert_eq!(t.range, (-64, 63));
+    }
+
+    #[test]
+    fn with_capacity_no_reallocation() {
+        let mut t = Tape::with_capacity(8);
+        for _ in 0..254 {
+            t.write(1);
+            t.move_head(Direction::Right);
+        }
+        for _ in 0..508 {
+            t.write(1);
+            t.move_head(Direction::Left);
+        }
+
+        assert_eq!(t.head, -254);
+        assert_eq!(t.cells.len(), 8);
+        assert_eq!(t.range, (-256, 255));
+        assert_eq!(t.count_ones(), 508);
+    }
+
+    #[test]
+    fn iter_cells() {
+        let mut t = Tape::new();
+        t.write(1);
+        t.move_head(Direction::Left);
+        t.write(1);
+        t.move_head(Direction::Left);
+        t.move_head(Direction::Left);
+        t.write(1);
+
+        let cells = t.iter_cells().collect::<Vec<u8>>();
+
+        assert_eq!(cells.len(), 128);
+        assert_eq!(&cells[61..65], &[1, 0, 1, 1]);
+        assert_eq!(cells.iter().filter(|&&symbol| symbol == 1).count(), 3);
+    }
+
+    #[test]
+    fn iter_nonblank_scattered() {
+        let mut t = Tape::new();
+
+        t.write(1);
+        for _ in 0..5 {
+            t.move_head(Direction::Right);
+        }
+        t.write(1);
+        for _ in 0..70 {
+            t.move_head(Direction::Right);
+        }
+        t.write(1);
+        for _ in 0..150 {
+            t.move_head(Direction::Left);
+        }
+        t.write(1);
+        t.move_head(Direction::Left);
+        t.write(1);
+
+        assert_eq!(t.iter_nonblank().count(), t.count_ones());
+        assert_eq!(
+            t.iter_nonblank().collect::<Vec<(isize, u8)>>(),
+            vec![(-76, 1), (-75, 1), (0, 1), (5, 1), (75, 1)]
+        );
+    }
+
+    #[test]
+    fn excursion() {
+        let mut t = Tape::new();
+        assert_eq!(t.excursion(), (0, 0));
+
+        for &direction in &[
+            Direction::Right,
+            Direction::Right,
+            Direction::Left,
+            Direction::Left,
+            Direction::Left,
+            Direction::Right,
+        ] {
+            t.move_head(direction);
+        }
+        assert_eq!(t.excursion(), (-1, 2));
+
+        t.move_head_by(Direction::Left, 100);
+        assert_eq!(t.excursion(), (-100, 2));
+        assert_eq!(t.range, (-128, 63));
+
+        t.reset();
+        assert_eq!(t.excursion(), (0, 0));
+        assert_eq!(Tape::from_symbols(&[1, 1], 5).excursion(), (5, 5));
+    }
+
+    #[test]
+    fn one_way_left_edge() {
+        let mut t = Tape::new_one_way();
+        assert!(t.is_one_way());
+        assert!(!Tape::new().is_one_way());
+
+        assert!(!t.move_head_one_way(Direction::Left));
+        assert_eq!(t.head, 0);
+
+        assert!(t.move_head_one_way(Direction::Right));
+        assert!(t.move_head_one_way(Direction::Left));
+        assert!(!t.move_head_one_way(Direction::Left));
+        assert_eq!(t.head, 0);
+        assert_eq!(t.excursion(), (0, 1));
+
+        let mut t = Tape::new();
+        assert!(t.move_head_one_way(Direction::Left));
+        assert_eq!(t.head, -1);
+    }
+
+    #[test]
+    fn one_way_allocation() {
+        let mut t = Tape::new_one_way();
+        assert_eq!(t.allocated_range(), (0, 127));
+
+        t.move_head_by(Direction::Right, 200);
+        t.move_head_by(Direction::Left, 200);
+        assert_eq!(t.head(), 0);
+        assert_eq!(t.allocated_range().0, 0);
+
+        t.write(1);
+        t.reset();
+        assert_eq!(t.allocated_range(), (0, 127));
+        assert_eq!(t.run_length(0, Direction::Left), Some(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "fell off")]
+    fn one_way_move_head_falls_off() {
+        Tape::new_one_way().move_head(Direction::Left);
+    }
+
+    #[test]
+    #[should_panic(expected = "fell off")]
+    fn one_way_move_head_by_falls_off() {
+        let mut t = Tape::new_one_way();
+        t.move_head_by(Direction::Right, 3);
+        t.move_head_by(Direction::Left, 4);
+    }
+
+    #[test]
+    fn try_move_head() {
+        let mut t = Tape::new();
+        t.head = 63;
+        t.write(1);
+
+        assert_eq!(
+            t.try_move_head(Direction::Right, 2),
+            Err(CellLimitExceeded { max_cells: 2 })
+        );
+        assert_eq!(t.head, 63);
+        assert_eq!(t.allocated_cells(), 2);
+        assert_eq!(t.excursion(), (0, 0));
+
+        assert_eq!(t.try_move_head(Direction::Right, 3), Ok(()));
+        assert_eq!(t.head, 64);
+        assert_eq!(t.allocated_cells(), 3);
+        assert_eq!(t.try_move_head(Direction::Left, 3), Ok(()));
+        assert_eq!(t.read(), 1);
+
+        assert_eq!(
+            CellLimitExceeded { max_cells: 2 }.to_string(),
+            "tape would exceed 2 cells"
+        );
+    }
+
+    #[test]
+    fn mirror() {
+        let mut t = Tape::from_symbols(&[1, 1, 0, 1, 0, 0, 1], 2);
+        t.move_head_by(Direction::Right, 100);
+        t.write(1);
+        t.move_head_by(Direction::Left, 97);
+
+        let mirror = t.mirror();
+        assert_ne!(mirror, t);
+        assert_eq!(mirror.head, t.head);
+        for offset in -150..=150 {
+            assert_eq!(mirror.peek(offset), t.peek(-offset), "offset {}", offset);
+        }
+        assert_eq!(mirror.excursion(), (-92, 8));
+        assert_eq!(mirror.writes_of(), t.writes_of());
+
+        assert_eq!(mirror.mirror(), t);
+        assert_eq!(Tape::new().mirror(), Tape::new());
+
+        let mut t = Tape::new_with_blank(1);
+        t.move_head(Direction::Left);
+        t.write(0);
+        t.move_head(Direction::Right);
+        assert_eq!(t.mirror().peek(1), 0);
+        assert_eq!(t.mirror().count_symbol(0), 1);
+        assert_eq!(t.mirror().mirror(), t);
+    }
+
+    #[test]
+    fn head_and_allocated_range() {
+        let mut t = Tape::new();
+        assert_eq!((t.head(), t.allocated_range()), (0, (-64, 63)));
+
+        t.move_head_by(Direction::Left, 64);
+        assert_eq!((t.head(), t.allocated_range()), (-64, (-64, 63)));
+
+        t.move_head(Direction::Left);
+        assert_eq!((t.head(), t.allocated_range()), (-65, (-128, 63)));
+
+        t.move_head_by(Direction::Right, 200);
+        assert_eq!((t.head(), t.allocated_range()), (135, (-128, 191)));
+        assert_eq!(t.head(), t.head);
+        assert_eq!(t.allocated_range(), t.range);
+
+        let t = PackedTape::<u8>::from_symbols(&[1; 10], 3);
+        assert_eq!((t.head(), t.allocated_range()), (3, (-8, 15)));
+    }
+
+    #[test]
+    fn from_symbols() {
+        let mut t = Tape::from_symbols(&[1, 0, 1, 1], 0);
+
+        assert_eq!(t.head, 0);
+        for &symbol in &[1, 0, 1, 1, 0] {
+            assert_eq!(t.read(), symbol);
+            t.move_head(Direction::Right);
+        }
+        assert_eq!(t.count_ones(), 3);
+        assert_eq!(t.allocated_cells(), 2);
+    }
+
+    #[test]
+    fn from_symbols_head_far_away() {
+        let t = Tape::from_symbols(&[1, 0, 1, 1], -130);
+
+        assert_eq!(t.head, -130);
+        assert_eq!(t.range, (-192, 63));
+        assert_eq!(t.read(), 0);
+        assert_eq!(t.window(0), vec![0]);
+        assert_eq!(t.symbol_at(2), 1);
+
+        let t = Tape::from_symbols(&[1; 100], 150);
+
+        assert_eq!(t.range, (-64, 191));
+        assert_eq!(t.count_ones(), 100);
+        assert_eq!(t.symbol_at(99), 1);
+        assert_eq!(t.symbol_at(100), 0);
+    }
+
+    #[test]
+    fn from_symbols_empty() {
+        assert_eq!(Tape::from_symbols(&[], 0), Tape::new());
+    }
+
+    #[test]
+    fn new_with_blank() {
+        let mut t = Tape::new_with_blank(1);
+        assert_eq!(t.read(), 1);
+
+        t.write(0);
+        for _ in 0..200 {
+            t.move_head(Direction::Right);
+        }
+
+        assert_eq!(t.read(), 1);
+        assert_eq!(t.window(1), vec![1, 1, 1]);
+        assert_eq!(t.count_symbol(0), 1);
+        assert_eq!(
+            t.iter_nonblank().collect::<Vec<(isize, u8)>>(),
+            vec![(0, 0)]
+        );
+        assert_eq!(t.run_length(1, Direction::Right), None);
+    }
+
+    #[test]
+    fn run_length_encode() {
+        let mut t = Tape::new();
+        for &symbol in &[0, 0, 0, 1, 1, 0, 1, 0, 0] {
+            t.write(symbol);
+            t.move_head(Direction::Right);
+        }
+
+        assert_eq!(
+            t.run_length_encode(),
+            vec![(0, 67), (1, 2), (0, 1), (1, 1), (0, 57)]
+        );
+        assert_eq!(Tape::new().run_length_encode(), vec![(0, 128)]);
+    }
+
+    #[test]
+    fn reset() {
+        let mut t = Tape::new();
+        t.sweep(1, Direction::Left, 300);
+        t.reset();
+
+        assert_eq!(t, Tape::new());
+        assert_eq!(t.head, 0);
+        assert!(t.iter_cells().all(|symbol| symbol == 0));
+        assert!(t.cells.capacity() >= 6);
+
+        let mut t = Tape::new_with_blank(1);
+        t.sweep(0, Direction::Right, 100);
+        t.reset();
+
+        assert_eq!(t, Tape::new_with_blank(1));
+
+        // A tape holding a single cell gets back its two cells
+        let mut t = Tape::new();
+        t.cells.truncate(1);
+        t.range = (-64, -1);
+        t.head = -1;
+        t.reset();
+
+        assert_eq!(t.cells.len(), 2);
+        assert_eq!(t.range, Tape::new().range);
+        assert_eq!(t, Tape::new());
+        t.move_head_by(Direction::Right, 63);
+        t.write(1);
+        assert_eq!(t.read(), 1);
+    }
+
+    #[test]
+    fn count_symbol() {
+        let mut t = Tape::new();
+        t.sweep(1, Direction::Right, 3);
+
+        assert_eq!(t.count_symbol(1), 3);
+        assert_eq!(t.count_symbol(0), 125);
+    }
+
+    #[test]
+    fn count_symbol_large_tape() {
+        // Every third symbol is a one over more than 15 000 cells
+        let symbols = (0..1_000_003)
+            .map(|i| u8::from(i % 3 == 0))
+            .collect::<Vec<u8>>();
+        let t = Tape::from_symbols(&symbols, 0);
+
+        assert_eq!(t.count_ones(), 333_335);
+        assert_eq!(t.count_symbol(0), 64 * t.allocated_cells() - 333_335);
+        assert_eq!(
+            PackedTape::<u8>::from_symbols(&symbols, 0).count_ones(),
+            333_335
+        );
+    }
+
+    #[test]
+    fn default() {
+        let t = Tape::default();
+
+        assert_eq!(t, Tape::new());
+    }
+
+    #[test]
+    fn fingerprint_base_inverse() {
+        assert_eq!(FINGERPRINT_BASE.wrapping_mul(FINGERPRINT_BASE_INVERSE), 1);
+    }
+
+    #[test]
+    fn window_fingerprint_equal_windows() {
+        let mut t = Tape::new();
+        t.write(1);
+        t.move_head_by(Direction::Right, 200);
+        t.write(1);
+
+        assert_eq!(t.window_fingerprint(3), t.window_fingerprint(3));
+        assert_eq!(t.window_fingerprint(3), {
+            let mut other = Tape::new();
+            other.write(1);
+            other.window_fingerprint(3)
+        });
+        assert_ne!(t.window_fingerprint(3), Tape::new().window_fingerprint(3));
+    }
+
+    #[test]
+    fn window_fingerprint_tracked_matches_computed() {
+        let mut rng = StdRng::seed_from_u64(49);
+        let mut t = Tape::new();
+        t.track_window(3);
+
+        for _ in 0..10_000 {
+            match rng.gen_range(0u8..4) {
+                0 => t.write(rng.gen_range(0..2)),
+                1 => t.move_head(Direction::Left),
+                2 => t.move_head(Direction::Right),
+                _ => t.move_head_by(Direction::random_with(&mut rng), rng.gen_range(0usize..100)),
+            }
+
+            assert_eq!(t.window_fingerprint(3), t.compute_fingerprint(3));
+        }
+
+        t.reset();
+        assert_eq!(t.window_fingerprint(3), Tape::new().window_fingerprint(3));
+    }
 }
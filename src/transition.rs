@@ -1,12 +1,22 @@
-use rand::{thread_rng, Rng};
-use std::fmt::{self, Display};
-use std::ops::RangeInclusive;
+use alloc::string::{String, ToString};
+use core::convert::TryFrom;
+use core::fmt::{self, Display};
+use core::ops::{Not, RangeInclusive};
+use core::str::FromStr;
+#[cfg(feature = "thread-rng")]
+use rand::thread_rng;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::error::Error;
 
 use crate::utils::map_range_inclusive;
 
 /// Container for action to perform given a binary symbol.
 #[repr(transparent)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Transition {
     /// First action corresponds to the action executed when a 0 is read.
     /// Second action corresponds to the action executed when a 1 is read.
@@ -29,6 +39,22 @@ impl Transition {
     pub fn get_action_of(&self, symbol: u8) -> (u8, Direction, State) {
         self.actions[symbol as usize].unpack()
     }
+
+    /// Returns the action executed when the given symbol is read.
+    #[inline]
+    #[must_use]
+    pub const fn action(&self, symbol: u8) -> Action {
+        self.actions[symbol as usize]
+    }
+
+    /// Checks if both actions write the same symbol, move in the same direction
+    /// and go to the same state, that is if the transition ignores the symbol
+    /// it reads.
+    #[inline]
+    #[must_use]
+    pub fn is_symbol_independent(&self) -> bool {
+        self.actions[0] == self.actions[1]
+    }
 }
 
 impl Display for Transition {
@@ -78,6 +104,13 @@ impl PartialTransition {
         self.actions[symbol as usize].map(Action::unpack)
     }
 
+    /// Returns the packed action to perform when the given symbol is read.
+    #[inline]
+    #[must_use]
+    pub const fn action(self, symbol: u8) -> Option<Action> {
+        self.actions[symbol as usize]
+    }
+
     /// Counts the number of specified actions.
     #[inline]
     #[must_use]
@@ -114,13 +147,21 @@ impl Display for PartialTransition {
 }
 
 /// Encoded action to perform on a transition.
+///
+/// With the `serde` feature it is serialized as a `{symbol, direction,
+/// next_state}` struct rather than as its packed representation.
 #[repr(transparent)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "ActionRepr", try_from = "ActionRepr")
+)]
 pub struct Action {
     /// Packed representation of the action:
     /// Bit 0: Symbol to write on the tape
     /// Bit 1: Direction in which to move the head (0 = Left, 1 = Right)
-    /// Bit 2-7: Next state
+    /// Bit 2-7: Next state (enough for up to 63 states, see `State::MAX_STATES`)
     representation: u8,
 }
 
@@ -146,6 +187,46 @@ impl Action {
         Direction::from(self.representation >> 1 & 1)
     }
 
+    /// Returns the state to go to.
+    #[inline]
+    #[must_use]
+    pub fn next_state(self) -> State {
+        State::from(self.representation >> 2)
+    }
+
+    /// Checks if the action goes to the halting state.
+    #[inline]
+    #[must_use]
+    pub fn is_halting(self) -> bool {
+        self.next_state().is_halting()
+    }
+
+    /// Returns the packed representation of the action.
+    ///
+    /// Bit 0 holds the symbol to write, bit 1 the direction (0 for left and 1
+    /// for right) and bits 2 to 7 the index of the next state (see
+    /// [`State::index`]).
+    #[inline]
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        self.representation
+    }
+
+    /// Creates an action from its packed representation, laid out as described
+    /// in [`Action::bits`].
+    ///
+    /// # Panics
+    /// Panics in debug mode if the encoded state is not a valid state.
+    #[inline]
+    #[must_use]
+    pub fn from_bits(bits: u8) -> Self {
+        debug_assert!(bits >> 2 <= State::Halt1 as u8);
+
+        Self {
+            representation: bits,
+        }
+    }
+
     /// Unpacks the representation to return corresponding symbol to write, direction and state.
     #[inline]
     #[must_use]
@@ -153,7 +234,7 @@ impl Action {
         (
             self.representation & 1,
             self.get_direction(),
-            State::from(self.representation >> 2),
+            self.next_state(),
         )
     }
 }
@@ -167,14 +248,74 @@ impl Display for Action {
             "{}{}{}",
             symbol,
             direction.to_str().chars().next().unwrap(),
-            state.to_str().chars().next().unwrap()
+            state.letter()
         )
     }
 }
 
+impl TryFrom<&str> for Action {
+    type Error = ParseError;
+
+    /// Parses an action written as `<symbol><direction><state>` (e.g. `1RB`),
+    /// the halting states being written `Z` or `-` and `Y`.
+    fn try_from(token: &str) -> Result<Self, Self::Error> {
+        if token.len() != 3 || !token.is_ascii() {
+            return Err(ParseError::InvalidAction(token.to_string()));
+        }
+
+        let symbol = match &token[..1] {
+            "0" => 0,
+            "1" => 1,
+            symbol => return Err(ParseError::InvalidSymbol(symbol.to_string())),
+        };
+        let direction = token[1..2].parse()?;
+        let state = token[2..].parse()?;
+
+        Ok(Self::new(symbol, direction, state))
+    }
+}
+
+/// Serialized form of an `Action`.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct ActionRepr {
+    symbol: u8,
+    direction: Direction,
+    next_state: State,
+}
+
+#[cfg(feature = "serde")]
+impl From<Action> for ActionRepr {
+    #[inline]
+    fn from(action: Action) -> Self {
+        let (symbol, direction, next_state) = action.unpack();
+
+        Self {
+            symbol,
+            direction,
+            next_state,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ActionRepr> for Action {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(repr: ActionRepr) -> Result<Self, Self::Error> {
+        if repr.symbol >= 2 {
+            return Err(ParseError::InvalidSymbol(repr.symbol.to_string()));
+        }
+
+        Ok(Self::new(repr.symbol, repr.direction, repr.next_state))
+    }
+}
+
 /// Direction in which to move the head
 #[repr(u8)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Direction {
     /// Left direction
     Left,
@@ -186,14 +327,33 @@ impl Direction {
     /// Returns a random direction.
     ///
     /// The distribution is uniform.
+    #[cfg(feature = "thread-rng")]
     #[inline]
     #[must_use]
     pub fn random() -> Self {
-        let mut rng = thread_rng();
+        Self::random_with(&mut thread_rng())
+    }
 
+    /// Returns a random direction drawn from the given random number generator.
+    ///
+    /// The distribution is uniform.
+    #[inline]
+    #[must_use]
+    pub fn random_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
         Self::from(rng.gen_range(0..=1))
     }
 
+    /// Returns the displacement of the head moving in the direction, -1 to
+    /// the left and 1 to the right.
+    #[inline(always)]
+    #[must_use]
+    pub const fn delta(self) -> i8 {
+        match self {
+            Self::Left => -1,
+            Self::Right => 1,
+        }
+    }
+
     /// Returns the string representation of the direction.
     #[inline]
     const fn to_str(self) -> &'static str {
@@ -204,6 +364,32 @@ impl Direction {
     }
 }
 
+impl Not for Direction {
+    type Output = Self;
+
+    /// Returns the opposite direction.
+    #[inline]
+    fn not(self) -> Self::Output {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    /// Parses a direction written `L`, `Left`, `R` or `Right`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "l" | "left" => Ok(Self::Left),
+            "r" | "right" => Ok(Self::Right),
+            _ => Err(ParseError::InvalidDirection(s.to_string())),
+        }
+    }
+}
+
 impl From<u8> for Direction {
     #[inline]
     #[must_use]
@@ -220,9 +406,17 @@ impl From<u8> for Direction {
 
 /// State of Turing machine
 ///
-/// Considering the complexity of the problem there is only 7 possible non-halting states.
+/// Considering the complexity of the problem there is only `MAX_STATES` (15)
+/// possible non-halting states. The packed representation of `Action` stores
+/// the next state on 6 bits so it could hold up to 63 states.
+///
+/// There are two halting states, `Halt` (also named `Halt0`) and `Halt1`, for
+/// formalisms distinguishing how a machine halts (e.g. accept and reject).
+///
+/// States are ordered by their [`State::index`].
 #[repr(u8)]
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum State {
     /// Halt state
     Halt,
@@ -240,9 +434,78 @@ pub enum State {
     F,
     /// G state
     G,
+    /// H state
+    H,
+    /// I state
+    I,
+    /// J state
+    J,
+    /// K state
+    K,
+    /// L state
+    L,
+    /// M state
+    M,
+    /// N state
+    N,
+    /// O state
+    O,
+    /// Secondary halt state
+    Halt1,
 }
 
 impl State {
+    /// Maximum number of non-halting states.
+    pub const MAX_STATES: usize = 15;
+
+    /// Primary halt state, an alias of `Halt`.
+    #[allow(non_upper_case_globals)]
+    pub const Halt0: Self = Self::Halt;
+
+    /// Number of states, halting states included.
+    pub const COUNT: usize = Self::ALL.len();
+
+    /// Every state, halting states included, in the order of their
+    /// representation.
+    pub const ALL: [Self; Self::MAX_STATES + 2] = [
+        Self::Halt,
+        Self::A,
+        Self::B,
+        Self::C,
+        Self::D,
+        Self::E,
+        Self::F,
+        Self::G,
+        Self::H,
+        Self::I,
+        Self::J,
+        Self::K,
+        Self::L,
+        Self::M,
+        Self::N,
+        Self::O,
+        Self::Halt1,
+    ];
+
+    /// Every non-halting state, from `A` to the last one.
+    pub const NON_HALTING: [Self; Self::MAX_STATES] = [
+        Self::A,
+        Self::B,
+        Self::C,
+        Self::D,
+        Self::E,
+        Self::F,
+        Self::G,
+        Self::H,
+        Self::I,
+        Self::J,
+        Self::K,
+        Self::L,
+        Self::M,
+        Self::N,
+        Self::O,
+    ];
+
     /// Returns the starting state.
     /// As the order of the state does not matter we always start in state A by
     /// convention.
@@ -255,19 +518,50 @@ impl State {
     /// Returns a random state in the given range.
     ///
     /// The distribution is uniform.
+    #[cfg(feature = "thread-rng")]
     #[inline]
     #[must_use]
     pub fn random(range: RangeInclusive<Self>) -> Self {
-        let mut rng = thread_rng();
+        Self::random_with(range, &mut thread_rng())
+    }
 
-        Self::from(rng.gen_range(map_range_inclusive(range, |s| s as u8)))
+    /// Returns a random state in the given range drawn from the given random
+    /// number generator.
+    ///
+    /// The distribution is uniform.
+    #[inline]
+    #[must_use]
+    pub fn random_with<R: Rng + ?Sized>(range: RangeInclusive<Self>, rng: &mut R) -> Self {
+        random_state_in(range, rng)
     }
 
-    /// Checks if the state is halting.
+    /// Returns the state following this one or `None` if this is the last
+    /// state.
+    ///
+    /// The state following `Halt` is the starting state.
+    #[inline]
+    #[must_use]
+    pub fn next(self) -> Option<Self> {
+        if self.index() < Self::MAX_STATES {
+            #[allow(clippy::cast_possible_truncation)]
+            Some(Self::from(self.index() as u8 + 1))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the index of the state, `Halt` being 0 and `A` being 1.
+    #[inline]
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Checks if the state is one of the halting states.
     #[inline(always)]
     #[must_use]
-    pub fn is_halting(self) -> bool {
-        self == Self::Halt
+    pub const fn is_halting(self) -> bool {
+        matches!(self, Self::Halt | Self::Halt1)
     }
 
     /// Returns the string representation of the state.
@@ -282,6 +576,26 @@ impl State {
             Self::E => "E",
             Self::F => "F",
             Self::G => "G",
+            Self::H => "H",
+            Self::I => "I",
+            Self::J => "J",
+            Self::K => "K",
+            Self::L => "L",
+            Self::M => "M",
+            Self::N => "N",
+            Self::O => "O",
+            Self::Halt1 => "Halt1",
+        }
+    }
+
+    /// Returns the letter of the state in the standard text format, `Z` and
+    /// `Y` standing for the halting states.
+    #[inline]
+    pub(crate) const fn letter(self) -> char {
+        match self {
+            Self::Halt => 'Z',
+            Self::Halt1 => 'Y',
+            _ => (b'A' + self as u8 - 1) as char,
         }
     }
 }
@@ -290,7 +604,7 @@ impl From<u8> for State {
     #[inline]
     #[must_use]
     fn from(state: u8) -> Self {
-        debug_assert!(state <= 7);
+        debug_assert!(state as usize <= Self::MAX_STATES || state == Self::Halt1 as u8);
 
         match state {
             0 => Self::Halt,
@@ -301,7 +615,377 @@ impl From<u8> for State {
             5 => Self::E,
             6 => Self::F,
             7 => Self::G,
+            8 => Self::H,
+            9 => Self::I,
+            10 => Self::J,
+            11 => Self::K,
+            12 => Self::L,
+            13 => Self::M,
+            14 => Self::N,
+            15 => Self::O,
+            16 => Self::Halt1,
             _ => unreachable!(),
         }
     }
 }
+
+impl FromStr for State {
+    type Err = ParseError;
+
+    /// Parses a state written as its letter (`A` to `O`), as `Halt`, `Halt0`,
+    /// `Z` or `-` for the primary halting state and as `Halt1` or `Y` for the
+    /// secondary one.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Halt" | "Halt0" | "Z" | "-" => Ok(Self::Halt),
+            "Halt1" | "Y" => Ok(Self::Halt1),
+            _ => non_halting_states(Self::MAX_STATES)
+                .find(|state| state.to_str() == s)
+                .ok_or_else(|| ParseError::InvalidState(s.to_string())),
+        }
+    }
+}
+
+/// Returns an iterator over the first `n` non-halting states, starting from `A`.
+///
+/// # Panics
+/// Panics if `n > State::MAX_STATES`.
+#[inline]
+#[must_use]
+pub fn non_halting_states(n: usize) -> impl DoubleEndedIterator<Item = State> + ExactSizeIterator {
+    assert!(n <= State::MAX_STATES);
+
+    #[allow(clippy::cast_possible_truncation)]
+    state_range(State::A, State::from(n as u8))
+}
+
+/// Returns an iterator over the states from `lo` to `hi` included, in order.
+///
+/// The iterator is empty if `hi` comes before `lo`.
+#[inline]
+#[must_use]
+pub fn state_range(
+    lo: State,
+    hi: State,
+) -> impl DoubleEndedIterator<Item = State> + ExactSizeIterator {
+    (lo as u8..=hi as u8).map(State::from)
+}
+
+/// Returns a random state in the given range drawn from the given random
+/// number generator.
+///
+/// The distribution is uniform.
+///
+/// # Panics
+/// Panics if the range is empty.
+#[inline]
+#[must_use]
+pub fn random_state_in<R: Rng + ?Sized>(range: RangeInclusive<State>, rng: &mut R) -> State {
+    State::from(rng.gen_range(map_range_inclusive(range, |s| s as u8)))
+}
+
+/// Error returned when parsing the textual representation of a machine fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The number of state blocks does not match the number of states.
+    StateCount {
+        /// Number of states of the machine.
+        expected: usize,
+        /// Number of state blocks found.
+        found: usize,
+    },
+    /// A state block is not made of exactly two actions.
+    InvalidBlock(String),
+    /// An action is not made of a symbol, a direction and a state.
+    InvalidAction(String),
+    /// A symbol is not `0` or `1`.
+    InvalidSymbol(String),
+    /// A direction is not `L` or `R`.
+    InvalidDirection(String),
+    /// A state is not a valid state name.
+    InvalidState(String),
+}
+
+impl Display for ParseError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StateCount { expected, found } => {
+                write!(f, "expected {} state blocks, found {}", expected, found)
+            }
+            Self::InvalidBlock(block) => write!(f, "invalid state block `{}`", block),
+            Self::InvalidAction(action) => write!(f, "invalid action `{}`", action),
+            Self::InvalidSymbol(symbol) => write!(f, "invalid symbol `{}`", symbol),
+            Self::InvalidDirection(direction) => write!(f, "invalid direction `{}`", direction),
+            Self::InvalidState(state) => write!(f, "invalid state `{}`", state),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn action_try_from_str() {
+        assert_eq!(
+            Action::try_from("0LA"),
+            Ok(Action::new(0, Direction::Left, State::A))
+        );
+        assert_eq!(
+            Action::try_from("1RZ"),
+            Ok(Action::new(1, Direction::Right, State::Halt))
+        );
+        assert_eq!(
+            Action::try_from("1R-"),
+            Ok(Action::new(1, Direction::Right, State::Halt))
+        );
+    }
+
+    #[test]
+    fn action_try_from_str_invalid() {
+        assert_eq!(
+            Action::try_from("2LA"),
+            Err(ParseError::InvalidSymbol("2".to_string()))
+        );
+        assert_eq!(
+            Action::try_from("1XB"),
+            Err(ParseError::InvalidDirection("X".to_string()))
+        );
+        assert_eq!(
+            Action::try_from("1LP"),
+            Err(ParseError::InvalidState("P".to_string()))
+        );
+        assert_eq!(
+            Action::try_from("1LBB"),
+            Err(ParseError::InvalidAction("1LBB".to_string()))
+        );
+        assert_eq!(
+            Action::try_from("1L"),
+            Err(ParseError::InvalidAction("1L".to_string()))
+        );
+        assert_eq!(
+            Action::try_from("1Lé"),
+            Err(ParseError::InvalidAction("1Lé".to_string()))
+        );
+    }
+
+    #[test]
+    fn transition_is_symbol_independent() {
+        let action = Action::new(1, Direction::Left, State::B);
+
+        assert!(Transition::new(action, action).is_symbol_independent());
+        assert!(
+            !Transition::new(action, Action::new(1, Direction::Left, State::C))
+                .is_symbol_independent()
+        );
+        assert!(
+            !Transition::new(action, Action::new(0, Direction::Left, State::B))
+                .is_symbol_independent()
+        );
+        assert!(
+            !Transition::new(action, Action::new(1, Direction::Right, State::B))
+                .is_symbol_independent()
+        );
+    }
+
+    #[test]
+    fn direction_delta() {
+        assert_eq!(Direction::Left.delta(), -1);
+        assert_eq!(Direction::Right.delta(), 1);
+    }
+
+    #[test]
+    fn direction_not() {
+        assert_eq!(!Direction::Left, Direction::Right);
+        assert_eq!(!Direction::Right, Direction::Left);
+        assert_eq!(!!Direction::Left, Direction::Left);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_action_struct_form() {
+        let action = Action::new(1, Direction::Left, State::B);
+        let json = serde_json::to_string(&action).unwrap();
+
+        assert_eq!(json, r#"{"symbol":1,"direction":"Left","next_state":"B"}"#);
+
+        let (symbol, direction, state) = serde_json::from_str::<Action>(&json).unwrap().unpack();
+        assert_eq!(symbol, 1);
+        assert_eq!(direction, Direction::Left);
+        assert_eq!(state, State::B);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_action_rejects_invalid_symbol() {
+        let json = r#"{"symbol":2,"direction":"Left","next_state":"B"}"#;
+
+        assert!(serde_json::from_str::<Action>(json).is_err());
+    }
+
+    #[test]
+    fn action_bits_round_trip() {
+        for state in state_range(State::Halt, State::Halt1) {
+            for &direction in &[Direction::Left, Direction::Right] {
+                for symbol in 0..2 {
+                    let action = Action::new(symbol, direction, state);
+                    let bits = action.bits();
+
+                    assert_eq!(bits, (state as u8) << 2 | (direction as u8) << 1 | symbol);
+                    assert_eq!(Action::from_bits(bits), action);
+                    assert_eq!(Action::from_bits(bits).unpack(), (symbol, direction, state));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "assertion failed")]
+    fn action_from_invalid_bits() {
+        let _ = Action::from_bits(17 << 2);
+    }
+
+    #[test]
+    fn action_next_state() {
+        let halting = Action::new(1, Direction::Right, State::Halt);
+        assert!(halting.is_halting());
+        assert_eq!(halting.next_state(), State::Halt);
+
+        let halting = Action::new(0, Direction::Left, State::Halt1);
+        assert!(halting.is_halting());
+        assert_eq!(halting.next_state(), State::Halt1);
+        assert_eq!(halting.to_string(), "0LY");
+
+        let action = Action::new(0, Direction::Left, State::C);
+        assert!(!action.is_halting());
+        assert_eq!(action.next_state(), State::C);
+        assert_eq!(action.get_direction(), Direction::Left);
+    }
+
+    #[test]
+    fn random_with_std_rng() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..100 {
+            let state = State::random_with(State::B..=State::D, &mut rng);
+            assert!(State::B.index() <= state.index() && state.index() <= State::D.index());
+        }
+
+        let directions = (0..100)
+            .map(|_| Direction::random_with(&mut rng))
+            .collect::<Vec<Direction>>();
+        assert!(directions.contains(&Direction::Left));
+        assert!(directions.contains(&Direction::Right));
+    }
+
+    #[test]
+    fn direction_from_str() {
+        for s in &["L", "l", "Left", "left", "LEFT"] {
+            assert_eq!(s.parse::<Direction>(), Ok(Direction::Left));
+        }
+        for s in &["R", "r", "Right", "right", "RIGHT"] {
+            assert_eq!(s.parse::<Direction>(), Ok(Direction::Right));
+        }
+
+        assert_eq!(
+            "Up".parse::<Direction>(),
+            Err(ParseError::InvalidDirection("Up".to_string()))
+        );
+        assert!("".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn state_all() {
+        assert_eq!(State::COUNT, 17);
+
+        for (index, &state) in (0u8..).zip(State::ALL.iter()) {
+            assert_eq!(state.index(), usize::from(index));
+            assert_eq!(State::from(index), state);
+            assert_eq!(State::ALL.iter().filter(|&&s| s == state).count(), 1);
+        }
+
+        assert_eq!(&State::ALL[1..=State::MAX_STATES], &State::NON_HALTING[..]);
+        assert!(State::NON_HALTING.iter().all(|state| !state.is_halting()));
+        assert!(State::NON_HALTING
+            .iter()
+            .copied()
+            .eq(non_halting_states(State::MAX_STATES)));
+    }
+
+    #[test]
+    fn state_from_str() {
+        for s in &["Halt", "Halt0", "Z", "-"] {
+            assert_eq!(s.parse::<State>(), Ok(State::Halt));
+        }
+        for s in &["Halt1", "Y"] {
+            assert_eq!(s.parse::<State>(), Ok(State::Halt1));
+        }
+        for state in non_halting_states(State::MAX_STATES) {
+            assert_eq!(state.to_str().parse::<State>(), Ok(state));
+        }
+
+        assert_eq!(
+            "P".parse::<State>(),
+            Err(ParseError::InvalidState("P".to_string()))
+        );
+        assert!("a".parse::<State>().is_err());
+        assert!("AB".parse::<State>().is_err());
+    }
+
+    #[test]
+    fn state_next() {
+        assert_eq!(State::Halt.next(), Some(State::A));
+        assert_eq!(State::A.next(), Some(State::B));
+        assert_eq!(State::G.next(), Some(State::H));
+        assert_eq!(State::O.next(), None);
+    }
+
+    #[test]
+    fn state_index() {
+        assert_eq!(State::Halt.index(), 0);
+        assert_eq!(State::A.index(), 1);
+        assert_eq!(State::O.index(), State::MAX_STATES);
+    }
+
+    #[test]
+    fn non_halting_states_bounds() {
+        assert_eq!(non_halting_states(0).count(), 0);
+        assert_eq!(
+            non_halting_states(3).collect::<Vec<State>>(),
+            vec![State::A, State::B, State::C]
+        );
+        assert_eq!(non_halting_states(State::MAX_STATES).last(), Some(State::O));
+    }
+
+    #[test]
+    fn state_range_inclusive() {
+        assert_eq!(
+            state_range(State::B, State::D).collect::<Vec<State>>(),
+            vec![State::B, State::C, State::D]
+        );
+        assert_eq!(
+            state_range(State::Halt, State::O).len(),
+            State::MAX_STATES + 1
+        );
+        assert_eq!(state_range(State::C, State::C).len(), 1);
+        assert_eq!(state_range(State::C, State::B).len(), 0);
+    }
+
+    #[test]
+    fn random_state_in_range() {
+        let mut rng = StdRng::seed_from_u64(59);
+
+        for _ in 0..100 {
+            let state = random_state_in(State::B..=State::D, &mut rng);
+            assert!(state_range(State::B, State::D).any(|s| s == state));
+        }
+        assert_eq!(random_state_in(State::E..=State::E, &mut rng), State::E);
+    }
+}
@@ -1,184 +1,3941 @@
-use rand::{thread_rng, Rng};
-use std::convert::TryInto;
-use std::fmt::{self, Display};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::cmp::Ordering;
+use core::convert::{TryFrom, TryInto};
+use core::fmt::{self, Display};
+#[cfg(feature = "thread-rng")]
+use rand::thread_rng;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::error::Error;
 
-use crate::tape::Tape;
-use crate::transition::{Action, Direction, PartialTransition, State, Transition};
+use crate::quadruple::QuadrupleMachine;
+use crate::tape::{Cell, PackedTape, Tape};
+use crate::transition::{
+    non_halting_states, random_state_in, Action, Direction, ParseError, PartialTransition, State,
+    Transition,
+};
 
-/// A binary-alphabet Turing Machine with N non-halting states.
-#[derive(Debug)]
-pub struct TuringMachine<const N: usize> {
-    transitions: [Transition; N],
-}
+/// Maximum number of steps simulated to find the order in which the states of
+/// a machine are visited when canonicalizing it.
+const CANONICALIZE_MAX_STEPS: usize = 10_000;
 
-impl<const N: usize> TuringMachine<N> {
-    /// Creates a new binary-alphabet Turing Machine with the given transitions.
+/// Largest radius of the window fingerprinted by the cycle detector, beyond
+/// which configurations are also told apart by replaying the run.
+const CYCLE_WINDOW_MAX_RADIUS: usize = 1 << 16;
+
+/// Offset basis of the 64-bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Prime of the 64-bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Outcome of a halting run of a Turing machine.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RunOutcome {
+    /// Number of steps performed before halting.
+    pub steps: usize,
+    /// Number of ones written on the tape when the machine halted.
+    pub ones: usize,
+    /// Number of distinct cells visited by the head.
+    pub space: usize,
+    /// Halting state entered by the machine.
+    pub halt_state: State,
+    /// Number of times each symbol was written, indexed by symbol.
     ///
-    /// # Panics
-    /// Panics if `N < 2`.
+    /// Every step writes a symbol, rewriting the one read included, so they
+    /// sum to the number of steps.
+    pub writes_of: [usize; 2],
+}
+
+impl RunOutcome {
+    /// Creates the outcome of a run which halted in `halt_state` after `steps`
+    /// steps on the given tape.
+    #[inline]
+    #[allow(clippy::cast_sign_loss)]
+    fn new<C: Cell>(steps: usize, tape: &PackedTape<C>, halt_state: State) -> Self {
+        let (leftmost, rightmost) = tape.excursion();
+
+        Self {
+            steps,
+            ones: tape.count_ones(),
+            space: (rightmost - leftmost + 1) as usize,
+            halt_state,
+            writes_of: tape.writes_of(),
+        }
+    }
+}
+
+/// Configuration of a run of a Turing machine which can be saved and resumed
+/// with [`TuringMachine::run_resumable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Configuration {
+    /// Tape of the run.
+    tape: Tape,
+    /// Current state of the machine.
+    state: State,
+    /// Number of steps performed.
+    step: usize,
+}
+
+impl Configuration {
+    /// Creates the initial configuration of a run on the blank input.
     #[inline]
     #[must_use]
-    pub fn new(transitions: [Transition; N]) -> Self {
-        assert!(N >= 2);
+    pub fn new() -> Self {
+        Self {
+            tape: Tape::new(),
+            state: State::start(),
+            step: 0,
+        }
+    }
 
-        Self { transitions }
+    /// Returns the tape of the run.
+    #[inline]
+    #[must_use]
+    pub const fn tape(&self) -> &Tape {
+        &self.tape
     }
 
-    /// Runs the turing machine on the blank input for a maximum number of steps.
-    /// Returns `Some(productivity)` if the machine did halt and `None` otherwise.
-    pub fn run(&self, max_steps: usize) -> Option<usize> {
-        let mut current_state = State::start();
-        let mut tape = Tape::new();
+    /// Returns the current state of the machine.
+    #[inline]
+    #[must_use]
+    pub const fn state(&self) -> State {
+        self.state
+    }
 
-        let mut i = 0;
-        while !current_state.is_halting() && i < max_steps {
-            let (symbol, direction, new_state) =
-                self.transitions[current_state as usize - 1].get_action_of(tape.read());
+    /// Returns the number of steps performed.
+    #[inline]
+    #[must_use]
+    pub const fn step(&self) -> usize {
+        self.step
+    }
+}
 
-            tape.write(symbol);
-            tape.move_head(direction);
-            current_state = new_state;
-            i += 1;
-        }
+impl Default for Configuration {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Description of a single step of a Turing machine.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StepInfo {
+    /// Index of the step, starting from 0.
+    pub step: usize,
+    /// State of the machine before the step.
+    pub state: State,
+    /// Symbol read under the head.
+    pub read: u8,
+    /// Symbol written under the head.
+    pub written: u8,
+    /// Direction in which the head moves.
+    pub direction: Direction,
+    /// Position of the head before it moves.
+    pub head: isize,
+}
+
+/// Iterator running a Turing machine one step at a time.
+///
+/// This `struct` is created by [`TuringMachine::stepper`].
+#[derive(Debug, Clone)]
+pub struct Stepper<'a, const N: usize> {
+    /// Machine being run.
+    machine: &'a TuringMachine<N>,
+    /// Tape of the run.
+    tape: Tape,
+    /// Current state of the machine.
+    state: State,
+    /// Number of steps performed.
+    step: usize,
+}
+
+impl<const N: usize> Stepper<'_, N> {
+    /// Returns the tape of the run.
+    #[inline]
+    #[must_use]
+    pub const fn tape(&self) -> &Tape {
+        &self.tape
+    }
+
+    /// Returns the current state of the machine.
+    #[inline]
+    #[must_use]
+    pub const fn state(&self) -> State {
+        self.state
+    }
 
-        current_state.is_halting().then(|| tape.count_ones())
+    /// Returns the number of steps performed.
+    #[inline]
+    #[must_use]
+    pub const fn step(&self) -> usize {
+        self.step
     }
 }
 
-impl<const N: usize> Display for TuringMachine<N> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut transitions = self.transitions.iter();
-        write!(f, "{}", transitions.next().unwrap())?;
+impl<const N: usize> Iterator for Stepper<'_, N> {
+    type Item = StepInfo;
 
-        for t in transitions {
-            write!(f, " {}", t)?;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state.is_halting() {
+            return None;
         }
 
-        Ok(())
+        let read = self.tape.read();
+        let (written, direction, new_state) =
+            self.machine.transition(self.state).get_action_of(read);
+        let info = StepInfo {
+            step: self.step,
+            state: self.state,
+            read,
+            written,
+            direction,
+            head: self.tape.head(),
+        };
+
+        self.tape.write(written);
+        self.tape.move_head(direction);
+        self.state = new_state;
+        self.step += 1;
+
+        Some(info)
     }
 }
 
-impl<const N: usize> From<PartialTuringMachine<N>> for TuringMachine<N> {
-    fn from(machine: PartialTuringMachine<N>) -> Self {
-        Self::new(
-            machine
-                .transitions
-                .iter()
-                .map(|&transition| transition.into())
-                .collect::<Vec<Transition>>()
-                .try_into()
-                .unwrap(),
-        )
+/// Result of running a Turing machine with cycle detection.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CycleResult {
+    /// The machine halted.
+    Halted(RunOutcome),
+    /// The machine reached a configuration it was already in and thus never halts.
+    Cycles {
+        /// Step at which the repeated configuration was first reached.
+        start: usize,
+        /// Number of steps between two occurrences of the configuration.
+        period: usize,
+    },
+    /// The machine neither halted nor cycled within the step budget.
+    Unknown,
+}
+
+/// Status of a run of a Turing machine with cycle detection.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RunStatus {
+    /// The machine halted.
+    Halted(RunOutcome),
+    /// The machine neither halted nor cycled within the step budget.
+    ExceededSteps,
+    /// The machine reached a configuration it was already in and thus never halts.
+    Cycles {
+        /// Number of steps between two occurrences of the configuration.
+        period: usize,
+    },
+}
+
+impl From<CycleResult> for RunStatus {
+    #[inline]
+    fn from(result: CycleResult) -> Self {
+        match result {
+            CycleResult::Halted(outcome) => Self::Halted(outcome),
+            CycleResult::Cycles { period, .. } => Self::Cycles { period },
+            CycleResult::Unknown => Self::ExceededSteps,
+        }
     }
 }
 
-/// A binary-alphabet partial Turing Machine with N non-halting states
-#[derive(Debug)]
-pub struct PartialTuringMachine<const N: usize> {
-    transitions: [PartialTransition; N],
+/// A pattern repeating while drifting along the tape.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TranslatedCycle {
+    /// Step at which the pattern was first seen.
+    pub start: usize,
+    /// Number of steps between two occurrences of the pattern.
+    pub period: usize,
+    /// Displacement of the head between two occurrences of the pattern.
+    pub offset: isize,
 }
 
-impl<const N: usize> PartialTuringMachine<N> {
-    /// Creates a new partial Turing Machine
+/// A binary-alphabet Turing Machine with N non-halting states.
+///
+/// With the `serde` feature it is serialized as the sequence of its
+/// transitions.
+///
+/// Two machines are equal if and only if their transition tables are
+/// identical.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "Vec<Transition>", try_from = "Vec<Transition>")
+)]
+pub struct TuringMachine<const N: usize> {
+    transitions: [Transition; N],
+}
+
+impl<const N: usize> TuringMachine<N> {
+    /// Creates a new binary-alphabet Turing Machine with the given transitions.
     ///
     /// # Panics
-    /// Panics if `N < 2`.
+    /// Panics if `N == 0`, `N > State::MAX_STATES` or if an action goes to a
+    /// state greater than `N`.
     #[inline]
     #[must_use]
-    pub fn new(transitions: [PartialTransition; N]) -> Self {
-        assert!(N >= 2);
+    pub fn new(transitions: [Transition; N]) -> Self {
+        Self::try_new(transitions).unwrap()
+    }
 
-        Self { transitions }
+    /// Creates a new binary-alphabet Turing Machine with the given transitions,
+    /// checking that every action goes to one of the `N` states or halts.
+    ///
+    /// # Errors
+    /// Returns an `InvalidMachine` describing the first action going to a
+    /// state greater than `N`.
+    ///
+    /// # Panics
+    /// Panics if `N == 0` or `N > State::MAX_STATES`.
+    pub fn try_new(transitions: [Transition; N]) -> Result<Self, InvalidMachine> {
+        assert!(N >= 1 && N <= State::MAX_STATES);
+
+        for (transition, state) in transitions.iter().zip(non_halting_states(N)) {
+            for symbol in 0..2 {
+                let (_, _, target) = transition.get_action_of(symbol);
+                if !target.is_halting() && target.index() > N {
+                    return Err(InvalidMachine {
+                        state,
+                        symbol,
+                        target,
+                    });
+                }
+            }
+        }
+
+        Ok(Self { transitions })
     }
 
-    /// Adds the given transition to the `PartialTuringMachine`
+    /// Returns the transition of the given state.
+    ///
+    /// # Panics
+    /// Panics if `state` is the halting state or is greater than `N`.
     #[inline]
-    pub fn add_transition(&mut self, state: State, symbol: u8, action: Action) {
-        self.transitions[state as usize - 1].set_action_of(symbol, Some(action));
+    #[must_use]
+    pub fn transition(&self, state: State) -> &Transition {
+        assert!(!state.is_halting(), "the halting state has no transition");
+
+        &self.transitions[state.index() - 1]
     }
 
-    /// Checks if the machine is N-state full
+    /// Returns the transitions of the machine, the transition of state A
+    /// coming first.
     #[inline]
     #[must_use]
-    fn is_n_state_full(&self) -> bool {
-        self.transitions
+    pub const fn transitions(&self) -> &[Transition; N] {
+        &self.transitions
+    }
+
+    /// Parses a machine from the standard text format (e.g. `1RB1LB_1LA1RZ`).
+    ///
+    /// Each state is described by a block of two actions, the first one being
+    /// executed when a 0 is read and the second one when a 1 is read. Blocks are
+    /// separated by `_`. The halting state is written `Z` or `-` and `---`
+    /// denotes an undefined transition which halts.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the number of blocks is not `N`, if an action
+    /// is malformed or if an action goes to a state greater than `N`.
+    ///
+    /// # Panics
+    /// Panics if `N == 0` or `N > State::MAX_STATES`.
+    pub fn from_standard_text(s: &str) -> Result<Self, ParseError> {
+        let blocks = s.split('_').collect::<Vec<&str>>();
+        if blocks.len() != N {
+            return Err(ParseError::StateCount {
+                expected: N,
+                found: blocks.len(),
+            });
+        }
+
+        let transitions = blocks
+            .into_iter()
+            .map(|block| {
+                if block.len() != 6 || !block.is_ascii() {
+                    return Err(ParseError::InvalidBlock(block.to_string()));
+                }
+
+                Ok(Transition::new(
+                    parse_action(&block[..3], 0)?,
+                    parse_action(&block[3..], 1)?,
+                ))
+            })
+            .collect::<Result<Vec<Transition>, ParseError>>()?;
+
+        Ok(Self::try_new(transitions.try_into().unwrap())?)
+    }
+
+    /// Builds a machine from a table of actions written like in the standard
+    /// text format, each pair holding the actions on 0 and on 1 of a state
+    /// (e.g. `[("1RB", "1LB"), ("1LA", "1RZ")]`).
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if an action is malformed or if an action goes
+    /// to a state greater than `N`.
+    ///
+    /// # Panics
+    /// Panics if `N == 0` or `N > State::MAX_STATES`.
+    pub fn from_table(table: [(&str, &str); N]) -> Result<Self, ParseError> {
+        let transitions = table
             .iter()
-            .map(|t| t.get_action_of(0).or(t.get_action_of(1)).is_some())
-            .count()
-            .eq(&N)
+            .map(|&(on_0, on_1)| {
+                Ok(Transition::new(
+                    parse_action(on_0, 0)?,
+                    parse_action(on_1, 1)?,
+                ))
+            })
+            .collect::<Result<Vec<Transition>, ParseError>>()?;
+
+        Ok(Self::try_new(transitions.try_into().unwrap())?)
     }
 
-    /// Checks if the machine contains `N` transitions that moves the head to
-    /// the right when a zero is read with the given transition
+    /// Converts the machine to its quadruple form, whose `3 * N` states each
+    /// either write a symbol or move the head.
+    ///
+    /// See [`QuadrupleMachine`] for the details of the conversion.
+    #[inline]
     #[must_use]
-    fn is_0_dextrous_with(&self, state: State, symbol: u8, action: Action) -> bool {
+    pub fn to_quadruple_form(&self) -> QuadrupleMachine<N> {
+        QuadrupleMachine::from(self)
+    }
+
+    /// Formats the machine in the standard text format (e.g. `1RB1LB_1LA1RZ`).
+    ///
+    /// This is the inverse of [`TuringMachine::from_standard_text`].
+    #[must_use]
+    pub fn to_standard_text(&self) -> String {
         self.transitions
             .iter()
-            .enumerate()
-            .filter(|(s, t)| {
-                if *s + 1 == state as usize && symbol == 0 {
-                    action.get_direction() == Direction::Right
-                } else {
-                    t.get_action_of(0)
-                        .map_or(false, |(_, direction, _)| direction == Direction::Right)
-                }
+            .map(|transition| {
+                format!(
+                    "{}{}",
+                    format_action(transition.get_action_of(0)),
+                    format_action(transition.get_action_of(1))
+                )
             })
-            .count()
-            .eq(&N)
+            .collect::<Vec<String>>()
+            .join("_")
     }
 
-    /// Counts the number of specified transitions of the machine.
+    /// Encodes the machine in `2 * N` bytes, each action being packed in a byte
+    /// as returned by [`Action::bits`], from state A to the last state and
+    /// symbol 0 before symbol 1.
+    ///
+    /// This is the inverse of [`TuringMachine::from_bytes`].
     #[must_use]
-    fn count_specified_transitions(&self) -> usize {
+    pub fn to_bytes(&self) -> Vec<u8> {
         self.transitions
             .iter()
-            .map(|t| t.count_specified_actions())
-            .sum()
+            .flat_map(|transition| (0..2).map(move |symbol| transition.action(symbol).bits()))
+            .collect()
     }
 
-    /// Gets the first state with unspecified transitions or if none the last state.
+    /// Decodes a machine encoded by [`TuringMachine::to_bytes`].
+    ///
+    /// # Errors
+    /// Returns a `DecodeError` if there are not exactly `2 * N` bytes or if an
+    /// action goes to a state which does not exist or is greater than `N`.
+    ///
+    /// # Panics
+    /// Panics if `N == 0` or `N > State::MAX_STATES`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != 2 * N {
+            return Err(DecodeError::InvalidLength {
+                expected: 2 * N,
+                found: bytes.len(),
+            });
+        }
+
+        if let Some(index) = bytes
+            .iter()
+            .position(|&byte| byte >> 2 > State::Halt1 as u8)
+        {
+            return Err(DecodeError::InvalidAction {
+                index,
+                byte: bytes[index],
+            });
+        }
+
+        let transitions = bytes
+            .chunks(2)
+            .map(|actions| {
+                Transition::new(Action::from_bits(actions[0]), Action::from_bits(actions[1]))
+            })
+            .collect::<Vec<Transition>>();
+
+        Ok(Self::try_new(transitions.try_into().unwrap())?)
+    }
+
+    /// Decodes a `n`-state machine from a record of the
+    /// [bbchallenge](https://bbchallenge.org) seed database.
+    ///
+    /// A record holds 6 bytes per state, from state A to the last state, and
+    /// for each state the transition on 0 followed by the transition on 1.
+    /// A transition is encoded in 3 bytes: the symbol to write (0 or 1), the
+    /// direction (0 for right and 1 for left) and the next state (1 for A, 2
+    /// for B and so on). The next state 0 denotes an undefined transition,
+    /// which halts like `---` in the standard text format whatever its other
+    /// bytes.
+    ///
+    /// # Errors
+    /// Returns a `DbError` if `n` is not `N`, if there are not exactly `6 * n`
+    /// bytes, if a byte is out of range or if a transition goes to a state
+    /// greater than `N`.
+    ///
+    /// # Panics
+    /// Panics if `N == 0` or `N > State::MAX_STATES`.
+    pub fn from_db_record(bytes: &[u8], n: usize) -> Result<Self, DbError> {
+        if n != N {
+            return Err(DbError::StateCount {
+                expected: N,
+                found: n,
+            });
+        }
+        if bytes.len() != 6 * N {
+            return Err(DbError::InvalidLength {
+                expected: 6 * N,
+                found: bytes.len(),
+            });
+        }
+
+        let limits = [1, 1, State::MAX_STATES];
+        if let Some(index) =
+            (0..bytes.len()).find(|&index| usize::from(bytes[index]) > limits[index % 3])
+        {
+            return Err(DbError::InvalidByte {
+                index,
+                byte: bytes[index],
+            });
+        }
+
+        let action = |record: &[u8], read: u8| match *record {
+            [_, _, 0] => Action::new(read, Direction::Right, State::Halt),
+            [written, direction, state] => Action::new(
+                written,
+                if direction == 0 {
+                    Direction::Right
+                } else {
+                    Direction::Left
+                },
+                State::from(state),
+            ),
+            _ => unreachable!(),
+        };
+        let transitions = bytes
+            .chunks(6)
+            .map(|record| Transition::new(action(&record[..3], 0), action(&record[3..], 1)))
+            .collect::<Vec<Transition>>();
+
+        Ok(Self::try_new(transitions.try_into().unwrap())?)
+    }
+
+    /// Formats the state-transition graph of the machine in the Graphviz DOT
+    /// language.
+    ///
+    /// Nodes are the `N` states and the halting state and each of the `2 * N`
+    /// actions is an edge labeled `read/write,direction`.
     #[must_use]
-    fn state_choice_limit(&self) -> State {
-        let mut s = N;
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec!["digraph {".to_string()];
+        lines.extend(non_halting_states(N).map(|state| format!("    {};", state.to_str())));
+        lines.push("    Halt [shape=doublecircle];".to_string());
+        if self.transitions.iter().any(|transition| {
+            (0..2).any(|symbol| transition.get_action_of(symbol).2 == State::Halt1)
+        }) {
+            lines.push("    Halt1 [shape=doublecircle];".to_string());
+        }
+
+        for (transition, state) in self.transitions.iter().zip(non_halting_states(N)) {
+            for read in 0..2 {
+                let (written, direction, next_state) = transition.get_action_of(read);
+                let direction = match direction {
+                    Direction::Left => 'L',
+                    Direction::Right => 'R',
+                };
 
-        while s > 0 && self.transitions[s - 1].count_specified_actions() == 0 {
-            s -= 1;
+                lines.push(format!(
+                    "    {} -> {} [label=\"{}/{},{}\"];",
+                    state.to_str(),
+                    next_state.to_str(),
+                    read,
+                    written,
+                    direction
+                ));
+            }
         }
 
-        State::from(s as u8)
+        lines.push("}".to_string());
+        lines.join("\n")
     }
 
-    /// Runs the `PartialTuringMachine` on a blank tape
-    pub fn run(&self, max_steps: usize) -> Result<Option<usize>, (State, u8)> {
+    /// Returns the mirror image of the machine, obtained by flipping the
+    /// direction of every action.
+    ///
+    /// A machine and its mirror image halt after the same number of steps with
+    /// the same number of ones.
+    #[must_use]
+    pub fn mirror(&self) -> Self {
+        let mirror = |(symbol, direction, state): (u8, Direction, State)| {
+            Action::new(symbol, !direction, state)
+        };
+
+        Self::new(self.transitions.map(|transition| {
+            Transition::new(
+                mirror(transition.get_action_of(0)),
+                mirror(transition.get_action_of(1)),
+            )
+        }))
+    }
+
+    /// Returns the machine obtained by swapping the roles of the symbols 0 and
+    /// 1: every written symbol is flipped and the two actions of every
+    /// transition are exchanged.
+    ///
+    /// The swapped machine only behaves like the original one when it is run
+    /// on a tape whose blank symbol is 1, so it generally does not halt after
+    /// the same number of steps on the blank tape.
+    #[must_use]
+    pub fn swap_symbols(&self) -> Self {
+        let swap = |(symbol, direction, state): (u8, Direction, State)| {
+            Action::new(1 - symbol, direction, state)
+        };
+
+        Self::new(self.transitions.map(|transition| {
+            Transition::new(
+                swap(transition.get_action_of(1)),
+                swap(transition.get_action_of(0)),
+            )
+        }))
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps.
+    /// Returns `Some(outcome)` if the machine did halt and `None` otherwise.
+    ///
+    /// When a transition loops on its own state and writes back the symbol it
+    /// reads, the head is moved over the whole run of that symbol at once.
+    #[inline]
+    pub fn run(&self, max_steps: usize) -> Option<RunOutcome> {
+        self.run_on(&mut Tape::new(), max_steps)
+    }
+
+    /// Runs the turing machine like [`TuringMachine::run`] on the given tape,
+    /// which is reset beforehand so that it can be reused across runs without
+    /// reallocating.
+    ///
+    /// The tape may pack its symbols in cells of any width, e.g.
+    /// `PackedTape<u32>`.
+    ///
+    /// # Panics
+    /// Panics if the machine moves left of position 0 on a one-way tape, see
+    /// [`TuringMachine::run_one_way`] for a run that reports it instead.
+    pub fn run_on<C: Cell>(
+        &self,
+        tape: &mut PackedTape<C>,
+        max_steps: usize,
+    ) -> Option<RunOutcome> {
+        tape.reset();
+
+        self.run_from_tape(tape, max_steps)
+    }
+
+    /// Runs the turing machine like [`TuringMachine::run`] on a tape holding
+    /// `initial` from position 0 onwards, the head starting at position `head`.
+    /// Cells outside of `initial` are blank.
+    ///
+    /// # Panics
+    /// Panics in `debug` mode if `initial` holds a symbol other than 0 or 1.
+    #[must_use]
+    pub fn run_from(&self, initial: &[u8], head: isize, max_steps: usize) -> Option<RunOutcome> {
+        self.run_from_tape(&mut Tape::from_symbols(initial, head), max_steps)
+    }
+
+    /// Runs the turing machine on the given tape as it is, from the start
+    /// state and the current head position.
+    fn run_from_tape<C: Cell>(
+        &self,
+        tape: &mut PackedTape<C>,
+        max_steps: usize,
+    ) -> Option<RunOutcome> {
+        let mut current_state = State::start();
+        let mut i = 0;
+        self.advance(tape, &mut current_state, &mut i, max_steps)?;
+
+        current_state
+            .is_halting()
+            .then(|| RunOutcome::new(i, tape, current_state))
+    }
+
+    /// Advances the machine from the given state and step until it halts or
+    /// `step` reaches `max_steps`.
+    /// Returns `None` as soon as an infinite shift over blank cells is about to
+    /// be performed, leaving the configuration before it.
+    fn advance<C: Cell>(
+        &self,
+        tape: &mut PackedTape<C>,
+        state: &mut State,
+        step: &mut usize,
+        max_steps: usize,
+    ) -> Option<()> {
+        while !state.is_halting() && *step < max_steps {
+            let read = tape.read();
+            let (written, direction, new_state) =
+                self.transitions[*state as usize - 1].get_action_of(read);
+
+            if new_state == *state && written == read {
+                // An infinite shift over blank cells never halts
+                let length = tape.run_length(read, direction)?;
+                let count = length.min(max_steps - *step);
+
+                tape.rewrite_by(read, direction, count);
+                *step += count;
+            } else {
+                tape.write(written);
+                tape.move_head(direction);
+                *state = new_state;
+                *step += 1;
+            }
+        }
+
+        Some(())
+    }
+
+    /// Advances the given configuration by at most `extra_steps` steps and
+    /// tells whether the machine halted.
+    ///
+    /// Running a configuration in several calls gives the same result as
+    /// running it at once for the total number of steps, so long runs can be
+    /// checkpointed and resumed.
+    pub fn run_resumable(&self, config: &mut Configuration, extra_steps: usize) -> RunStatus {
+        let max_steps = config.step.saturating_add(extra_steps);
+
+        if self
+            .advance(
+                &mut config.tape,
+                &mut config.state,
+                &mut config.step,
+                max_steps,
+            )
+            .is_none()
+        {
+            // The head shifts forever so it moves by the remaining budget
+            let (_, direction, _) = self.transition(config.state).get_action_of(0);
+            config
+                .tape
+                .rewrite_by(0, direction, max_steps - config.step);
+            config.step = max_steps;
+        }
+
+        if config.state.is_halting() {
+            RunStatus::Halted(RunOutcome::new(config.step, &config.tape, config.state))
+        } else {
+            RunStatus::ExceededSteps
+        }
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps
+    /// calling `observer` before each step is performed.
+    /// Returns `Some(outcome)` if the machine did halt and `None` otherwise.
+    pub fn run_with<F: FnMut(&StepInfo)>(
+        &self,
+        max_steps: usize,
+        mut observer: F,
+    ) -> Option<RunOutcome> {
         let mut current_state = State::start();
         let mut tape = Tape::new();
 
         let mut i = 0;
         while !current_state.is_halting() && i < max_steps {
-            let (symbol, direction, new_state) = self.transitions[current_state as usize - 1]
-                .get_action_of(tape.read())
-                .ok_or((current_state, tape.read()))?;
+            let read = tape.read();
+            let (written, direction, new_state) =
+                self.transitions[current_state as usize - 1].get_action_of(read);
 
-            tape.write(symbol);
+            observer(&StepInfo {
+                step: i,
+                state: current_state,
+                read,
+                written,
+                direction,
+                head: tape.head(),
+            });
+
+            tape.write(written);
             tape.move_head(direction);
             current_state = new_state;
             i += 1;
         }
 
-        Ok(current_state.is_halting().then(|| tape.count_ones()))
+        current_state
+            .is_halting()
+            .then(|| RunOutcome::new(i, &tape, current_state))
     }
-}
-
-impl<const N: usize> Display for PartialTuringMachine<N> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut transitions = self.transitions.iter();
-        write!(f, "{}", transitions.next().unwrap())?;
 
-        for t in transitions {
-            write!(f, " {}", t)?;
+    /// Returns an iterator running the turing machine on the blank input one
+    /// step at a time, yielding the description of each step performed.
+    ///
+    /// The iterator ends when the machine halts, so it is endless for machines
+    /// which never halt.
+    #[inline]
+    #[must_use]
+    pub fn stepper(&self) -> Stepper<'_, N> {
+        Stepper {
+            machine: self,
+            tape: Tape::new(),
+            state: State::start(),
+            step: 0,
         }
+    }
 
-        Ok(())
+    /// Runs the turing machine on the blank input for a maximum number of steps.
+    /// Returns `Some(outcome)` if the machine did halt and `None` otherwise.
+    ///
+    /// When a transition loops on its own state, the machine keeps moving in
+    /// the same direction until it reads another symbol. Such sweeps over
+    /// uniform blocks are performed at once, a whole cell of symbols at a
+    /// time, without going through the transition table at every step, which
+    /// makes this faster than `run` on machines sweeping long tapes while
+    /// producing the same result.
+    #[inline]
+    #[must_use]
+    pub fn run_accelerated(&self, max_steps: usize) -> Option<RunOutcome> {
+        self.run_accelerated_counting(max_steps).0
     }
-}
 
+    /// Runs the turing machine like [`TuringMachine::run_accelerated`] and
+    /// also returns the number of transitions applied, a whole sweep counting
+    /// as a single one.
+    fn run_accelerated_counting(&self, max_steps: usize) -> (Option<RunOutcome>, usize) {
+        let mut current_state = State::start();
+        let mut tape = Tape::new();
+        let mut transitions = 0;
+
+        let mut i = 0;
+        while !current_state.is_halting() && i < max_steps {
+            let read = tape.read();
+            let (symbol, direction, new_state) =
+                self.transitions[current_state as usize - 1].get_action_of(read);
+            transitions += 1;
+
+            if new_state == current_state {
+                match tape.run_length(read, direction) {
+                    Some(length) => {
+                        let count = length.min(max_steps - i);
+
+                        tape.sweep(symbol, direction, count);
+                        i += count;
+                    }
+                    // An infinite sweep over blank cells never halts
+                    None => break,
+                }
+            } else {
+                tape.write(symbol);
+                tape.move_head(direction);
+                current_state = new_state;
+                i += 1;
+            }
+        }
+
+        let outcome = current_state
+            .is_halting()
+            .then(|| RunOutcome::new(i, &tape, current_state));
+
+        (outcome, transitions)
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps
+    /// without letting the tape grow beyond `max_cells` cells of 64 symbols.
+    /// Returns `Ok(Some(outcome))` if the machine did halt and `Ok(None)`
+    /// otherwise.
+    ///
+    /// # Errors
+    /// Returns a `TapeLimitExceeded` error as soon as the tape needs more than
+    /// `max_cells` cells.
+    pub fn run_bounded(
+        &self,
+        max_steps: usize,
+        max_cells: usize,
+    ) -> Result<Option<RunOutcome>, TapeLimitExceeded> {
+        let mut current_state = State::start();
+        let mut tape = Tape::new();
+
+        let mut i = 0;
+        while !current_state.is_halting() && i < max_steps {
+            let (symbol, direction, new_state) =
+                self.transitions[current_state as usize - 1].get_action_of(tape.read());
+
+            tape.write(symbol);
+            i += 1;
+            if tape.try_move_head(direction, max_cells).is_err() {
+                return Err(TapeLimitExceeded { max_cells, step: i });
+            }
+            current_state = new_state;
+        }
+
+        Ok(current_state
+            .is_halting()
+            .then(|| RunOutcome::new(i, &tape, current_state)))
+    }
+
+    /// Runs the turing machine for a maximum number of steps on a blank tape
+    /// which is infinite only to the right, the head starting on its leftmost
+    /// cell.
+    /// Returns `Ok(Some(outcome))` if the machine did halt and `Ok(None)`
+    /// otherwise.
+    ///
+    /// # Errors
+    /// Returns a `FellOffTape` error as soon as the machine moves left of the
+    /// leftmost cell.
+    pub fn run_one_way(&self, max_steps: usize) -> Result<Option<RunOutcome>, FellOffTape> {
+        let mut current_state = State::start();
+        let mut tape = Tape::new_one_way();
+
+        let mut i = 0;
+        while !current_state.is_halting() && i < max_steps {
+            let (symbol, direction, new_state) =
+                self.transitions[current_state as usize - 1].get_action_of(tape.read());
+
+            tape.write(symbol);
+            i += 1;
+            if !tape.move_head_one_way(direction) {
+                return Err(FellOffTape {
+                    state: current_state,
+                    step: i,
+                });
+            }
+            current_state = new_state;
+        }
+
+        Ok(current_state
+            .is_halting()
+            .then(|| RunOutcome::new(i, &tape, current_state)))
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps
+    /// while looking for a repeated configuration.
+    ///
+    /// A configuration is made of the state, the head position and the whole
+    /// tape contents so only machines repeating the exact same tape are caught.
+    /// Machines repeating a pattern while drifting along the tape (translated
+    /// cyclers) never repeat a configuration and are reported as
+    /// `CycleResult::Unknown`.
+    ///
+    /// Configurations are looked up by their state, head position and
+    /// [`Tape::window_fingerprint`] of a window covering the cells visited
+    /// within the budget, which is updated in constant time at every step.
+    /// Matching fingerprints are confirmed by replaying the run up to the
+    /// earlier configuration and comparing the whole tapes.
+    #[must_use]
+    pub fn detect_cycle(&self, max_steps: usize) -> CycleResult {
+        let mut current_state = State::start();
+        let mut tape = Tape::new();
+        // Visited cells are less than twice the budget away from the head
+        let radius = max_steps.saturating_mul(2).min(CYCLE_WINDOW_MAX_RADIUS);
+        tape.track_window(radius);
+        let mut seen: BTreeMap<_, Vec<usize>> = BTreeMap::new();
+
+        let mut i = 0;
+        while !current_state.is_halting() && i < max_steps {
+            let key = (current_state, tape.head(), tape.window_fingerprint(radius));
+            let starts = seen.entry(key).or_default();
+
+            if let Some(&start) = starts.iter().find(|&&start| self.tape_after(start) == tape) {
+                return CycleResult::Cycles {
+                    start,
+                    period: i - start,
+                };
+            }
+            starts.push(i);
+
+            current_state = self.step(current_state, &mut tape);
+            i += 1;
+        }
+
+        if current_state.is_halting() {
+            CycleResult::Halted(RunOutcome::new(i, &tape, current_state))
+        } else {
+            CycleResult::Unknown
+        }
+    }
+
+    /// Returns the tape after running the turing machine on the blank input
+    /// for `steps` steps, the machine not halting before.
+    fn tape_after(&self, steps: usize) -> Tape {
+        let mut current_state = State::start();
+        let mut tape = Tape::new();
+
+        for _ in 0..steps {
+            current_state = self.step(current_state, &mut tape);
+        }
+
+        tape
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps
+    /// and tells whether it halted, cycled or exceeded the step budget.
+    ///
+    /// Cycles are found with [`TuringMachine::detect_cycle`] so translated
+    /// cyclers are reported as `RunStatus::ExceededSteps`.
+    #[inline]
+    #[must_use]
+    pub fn run_status(&self, max_steps: usize) -> RunStatus {
+        self.detect_cycle(max_steps).into()
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps
+    /// while looking for a translated cycle.
+    ///
+    /// Each time the head reaches a cell further than ever before, the state
+    /// and the `window` symbols ending at the head on the visited side are
+    /// recorded. When a record comes back with the same state, direction and
+    /// window, the run is replayed up to the older record and the whole
+    /// segment swept since then is compared: if the cells from the furthest
+    /// the head went back up to the head match once shifted, the machine
+    /// repeats the same steps forever while drifting. The window only narrows
+    /// down the records to compare so no cycle is wrongly reported whatever
+    /// its size.
+    /// Returns `None` if the machine halts or if no cycle is found.
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn detect_translated_cycler(
+        &self,
+        max_steps: usize,
+        window: usize,
+    ) -> Option<TranslatedCycle> {
+        let mut current_state = State::start();
+        let mut tape = Tape::new();
+        // Step of each record with the lowest and highest head positions since
+        // the previous record
+        let mut history: Vec<(usize, (isize, isize))> = Vec::new();
+        let mut records = BTreeMap::new();
+        let (mut leftmost, mut rightmost) = (0, 0);
+        let (mut low, mut high) = (0, 0);
+
+        let mut i = 0;
+        while !current_state.is_halting() && i < max_steps {
+            let head = tape.head();
+            low = low.min(head);
+            high = high.max(head);
+
+            let direction = if head > rightmost {
+                rightmost = head;
+                Some(Direction::Right)
+            } else if head < leftmost {
+                leftmost = head;
+                Some(Direction::Left)
+            } else {
+                None
+            };
+
+            if let Some(direction) = direction {
+                let behind = -isize::from(direction.delta());
+                let key = (
+                    current_state,
+                    direction as u8,
+                    (0..window as isize)
+                        .map(|distance| tape.peek(distance * behind))
+                        .collect::<Vec<u8>>(),
+                );
+
+                history.push((i, (low, high)));
+                low = head;
+                high = head;
+
+                // Candidates are in step order so a single replay reaches them all
+                let mut replayed_state = State::start();
+                let mut replayed = Tape::new();
+                let mut replayed_steps = 0;
+
+                let index = history.len() - 1;
+                let candidates: &mut Vec<usize> = records.entry(key).or_default();
+                if let Some(&start) = candidates.iter().find(|&&start| {
+                    while replayed_steps < history[start].0 {
+                        replayed_state = self.step(replayed_state, &mut replayed);
+                        replayed_steps += 1;
+                    }
+
+                    Self::translated_repeat(&replayed, &tape, &history[start..], direction)
+                }) {
+                    return Some(TranslatedCycle {
+                        start: history[start].0,
+                        period: i - history[start].0,
+                        offset: head - replayed.head(),
+                    });
+                }
+                candidates.push(index);
+            }
+
+            current_state = self.step(current_state, &mut tape);
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Checks whether the run between the first and the last of the given
+    /// records, both reached moving the head in the given direction, repeats
+    /// forever, `first` and `last` being the tapes at these records.
+    ///
+    /// Cells past the head are blank at both records, so the run repeats if
+    /// the cells from the furthest the head went back since the first record
+    /// up to the head are the same at both records once shifted.
+    fn translated_repeat(
+        first: &Tape,
+        last: &Tape,
+        records: &[(usize, (isize, isize))],
+        direction: Direction,
+    ) -> bool {
+        let bounds = records[1..].iter().map(|&(_, bounds)| bounds);
+        let back = match direction {
+            Direction::Right => bounds.map(|(low, _)| low).fold(first.head(), isize::min),
+            Direction::Left => bounds.map(|(_, high)| high).fold(first.head(), isize::max),
+        };
+        let distance = back - first.head();
+
+        (distance.min(0)..=distance.max(0)).all(|offset| first.peek(offset) == last.peek(offset))
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps
+    /// while checking whether it spins out, that is moves forever in one
+    /// direction over blank cells.
+    ///
+    /// Each time the head reaches a cell never visited before, the transitions
+    /// on 0 are followed from the current state. The machine spins out if they
+    /// all move away from the visited cells and loop back to a state of the
+    /// chain, as the head then only ever reads blank cells.
+    /// Returns `false` if the machine halts or if no spin out is found.
+    #[must_use]
+    pub fn detects_spinout(&self, max_steps: usize) -> bool {
+        let mut current_state = State::start();
+        let mut tape = Tape::new();
+        let (mut leftmost, mut rightmost) = (0, 0);
+
+        let mut i = 0;
+        while !current_state.is_halting() && i < max_steps {
+            let head = tape.head();
+            let directions: &[Direction] = if i == 0 {
+                &[Direction::Left, Direction::Right]
+            } else if head > rightmost {
+                rightmost = head;
+                &[Direction::Right]
+            } else if head < leftmost {
+                leftmost = head;
+                &[Direction::Left]
+            } else {
+                &[]
+            };
+
+            if directions
+                .iter()
+                .any(|&direction| self.spins_out_from(current_state, direction))
+            {
+                return true;
+            }
+
+            current_state = self.step(current_state, &mut tape);
+            i += 1;
+        }
+
+        false
+    }
+
+    /// Checks if the transitions on 0 followed from the given state all move
+    /// in the given direction and loop back to a state of the chain.
+    fn spins_out_from(&self, mut state: State, direction: Direction) -> bool {
+        let mut visited = 0u32;
+
+        while !state.is_halting() {
+            if visited & (1 << state.index()) != 0 {
+                return true;
+            }
+            visited |= 1 << state.index();
+
+            let (_, action_direction, next_state) = self.transition(state).get_action_of(0);
+            if action_direction != direction {
+                return false;
+            }
+            state = next_state;
+        }
+
+        false
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps
+    /// and tells whether the head comes back to position 0 after leaving it.
+    ///
+    /// Returns `false` if the machine halts or the budget runs out before
+    /// the head returns to the origin.
+    #[must_use]
+    pub fn returns_to_origin(&self, max_steps: usize) -> bool {
+        let mut current_state = State::start();
+        let mut tape = Tape::new();
+
+        let mut i = 0;
+        while !current_state.is_halting() && i < max_steps {
+            current_state = self.step(current_state, &mut tape);
+            i += 1;
+
+            if tape.head() == 0 {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Tries to prove that the machine never halts on the blank input by
+    /// reasoning backward from its halting transitions.
+    ///
+    /// Starting from each configuration about to halt, the partial tape
+    /// contents of the configurations which could lead to it are explored up
+    /// to `depth` steps back. The machine never halts if every branch dies out
+    /// without reaching a configuration compatible with the initial one.
+    /// Returns `false` when no proof is found, which does not mean the machine
+    /// halts.
+    #[must_use]
+    pub fn proves_nonhalting_backward(&self, depth: usize) -> bool {
+        self.transitions
+            .iter()
+            .zip(non_halting_states(N))
+            .all(|(transition, state)| {
+                (0..2).all(|read| {
+                    let (_, _, next_state) = transition.get_action_of(read);
+                    let mut tape = BTreeMap::new();
+                    tape.insert(0, read);
+
+                    !next_state.is_halting()
+                        || !self.may_be_reached_backward(state, 0, &mut tape, depth)
+                })
+            })
+    }
+
+    /// Checks whether the configuration in the given state with the head at
+    /// `head` and the known symbols of `tape` may be reached from the initial
+    /// configuration, exploring at most `depth` steps back.
+    fn may_be_reached_backward(
+        &self,
+        state: State,
+        head: isize,
+        tape: &mut BTreeMap<isize, u8>,
+        depth: usize,
+    ) -> bool {
+        if state == State::start() && tape.values().all(|&symbol| symbol == 0) {
+            return true;
+        }
+        if depth == 0 {
+            return true;
+        }
+
+        for (transition, previous_state) in self.transitions.iter().zip(non_halting_states(N)) {
+            for read in 0..2 {
+                let (written, direction, next_state) = transition.get_action_of(read);
+                let previous_head = head - isize::from(direction.delta());
+                if next_state != state || tape.get(&previous_head).map_or(false, |&s| s != written)
+                {
+                    continue;
+                }
+
+                let known = tape.insert(previous_head, read);
+                let reached =
+                    self.may_be_reached_backward(previous_state, previous_head, tape, depth - 1);
+                match known {
+                    Some(symbol) => tape.insert(previous_head, symbol),
+                    None => tape.remove(&previous_head),
+                };
+
+                if reached {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps
+    /// and returns the non-halting states it enters, in the order they are
+    /// first visited.
+    ///
+    /// The run stops early once every state has been visited.
+    #[must_use]
+    pub fn reachable_states(&self, max_steps: usize) -> Vec<State> {
+        let mut states = vec![State::start()];
+        let mut current_state = State::start();
+        let mut tape = Tape::new();
+
+        let mut i = 0;
+        while !current_state.is_halting() && i < max_steps && states.len() < N {
+            current_state = self.step(current_state, &mut tape);
+            if !current_state.is_halting() && !states.contains(&current_state) {
+                states.push(current_state);
+            }
+            i += 1;
+        }
+
+        states
+    }
+
+    /// Relabels the states of the machine in the order they are first visited
+    /// when running on the blank input, which gives the same machine for all
+    /// the machines only differing by a permutation of their states.
+    ///
+    /// The machine is run for at most 10 000 steps. States not visited by then
+    /// are ordered after the visited ones in the order they are reached by
+    /// following the transitions of the ordered states, on 0 before 1. The
+    /// states which cannot be reached at all keep their original order.
+    ///
+    /// # Panics
+    /// Panics if the machine goes to a state greater than `N`.
+    #[must_use]
+    pub fn canonicalize(&self) -> Self {
+        let mut order = self.reachable_states(CANONICALIZE_MAX_STEPS);
+        let mut i = 0;
+        while i < order.len() {
+            let transition = self.transition(order[i]);
+            for symbol in 0..2 {
+                let (_, _, target) = transition.get_action_of(symbol);
+                if !target.is_halting() && !order.contains(&target) {
+                    order.push(target);
+                }
+            }
+            i += 1;
+        }
+        for state in non_halting_states(N) {
+            if !order.contains(&state) {
+                order.push(state);
+            }
+        }
+
+        let mut relabeling = [State::Halt; State::MAX_STATES + 2];
+        relabeling[State::Halt1.index()] = State::Halt1;
+        for (&state, relabeled) in order.iter().zip(non_halting_states(N)) {
+            relabeling[state.index()] = relabeled;
+        }
+
+        let relabel = |(symbol, direction, state): (u8, Direction, State)| {
+            Action::new(symbol, direction, relabeling[state.index()])
+        };
+
+        Self::new(
+            order
+                .iter()
+                .map(|&state| {
+                    let transition = self.transitions[state as usize - 1];
+                    Transition::new(
+                        relabel(transition.get_action_of(0)),
+                        relabel(transition.get_action_of(1)),
+                    )
+                })
+                .collect::<Vec<Transition>>()
+                .try_into()
+                .unwrap(),
+        )
+    }
+
+    /// Formats the canonical form of the machine, as given by
+    /// [`TuringMachine::canonicalize`], in the standard text format.
+    ///
+    /// Machines only differing by a relabeling of their states are formatted
+    /// the same, the primary halting state being written `Z`.
+    #[must_use]
+    pub fn normalized_text(&self) -> String {
+        self.canonicalize().to_standard_text()
+    }
+
+    /// Returns a hash of the canonical form of the machine, as given by
+    /// [`TuringMachine::canonicalize`].
+    ///
+    /// Machines only differing by a relabeling of their states share the same
+    /// key, which can be stored instead of whole machines to remember the
+    /// machines already seen. The key is the FNV-1a hash of the canonical
+    /// actions so it is the same across runs and platforms.
+    #[must_use]
+    pub fn canonical_key(&self) -> u64 {
+        self.canonicalize()
+            .transitions
+            .iter()
+            .flat_map(|t| (0..2).map(move |symbol| t.action(symbol).bits()))
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+            })
+    }
+
+    /// Returns a hash of the structure of the machine which does not depend on
+    /// how its states are labeled, computed without running it.
+    ///
+    /// Each state is summarized by the symbols its actions write, their
+    /// directions and whether they loop on the state, go to another state or
+    /// halt, and by whether it is the start state. The sorted summaries are
+    /// hashed with FNV-1a. Machines with different signatures are never
+    /// relabelings of one another but machines sharing a signature may still
+    /// differ, unlike with [`TuringMachine::canonical_key`].
+    #[must_use]
+    pub fn structural_signature(&self) -> u64 {
+        let mut summaries = non_halting_states(N)
+            .map(|state| {
+                let summary = |symbol: u8| {
+                    let (written, direction, next_state) =
+                        self.transition(state).get_action_of(symbol);
+                    let target = match next_state {
+                        State::Halt => 0,
+                        State::Halt1 => 1,
+                        next_state if next_state == state => 2,
+                        _ => 3,
+                    };
+
+                    written | u8::from(direction == Direction::Left) << 1 | target << 2
+                };
+
+                u16::from(state == State::start()) << 8
+                    | u16::from(summary(0)) << 4
+                    | u16::from(summary(1))
+            })
+            .collect::<Vec<u16>>();
+        summaries.sort_unstable();
+
+        summaries
+            .iter()
+            .flat_map(|summary| summary.to_le_bytes())
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+            })
+    }
+
+    /// Runs both machines on the blank input for a maximum number of steps and
+    /// checks whether they behave the same.
+    ///
+    /// At every step both machines must write the same symbol and move in the
+    /// same direction, so their heads stay together, and both must halt at
+    /// the same step in the same halting state. Non-halting states are compared
+    /// up to a consistent relabeling, thus a machine behaves like its
+    /// canonical form.
+    #[must_use]
+    pub fn behaviorally_equal(&self, other: &Self, max_steps: usize) -> bool {
+        // Relabeling of the states of `self` into those of `other` and back
+        let mut relabeling = [None; State::COUNT];
+        let mut inverse = [None; State::COUNT];
+        for &halt in &[State::Halt, State::Halt1] {
+            relabeling[halt.index()] = Some(halt);
+            inverse[halt.index()] = Some(halt);
+        }
+
+        let (mut state, mut other_state) = (State::start(), State::start());
+        let (mut tape, mut other_tape) = (Tape::new(), Tape::new());
+
+        for i in 0..=max_steps {
+            match (relabeling[state.index()], inverse[other_state.index()]) {
+                (None, None) => {
+                    relabeling[state.index()] = Some(other_state);
+                    inverse[other_state.index()] = Some(state);
+                }
+                (Some(relabeled), _) if relabeled == other_state => {}
+                _ => return false,
+            }
+
+            if state.is_halting() || i == max_steps {
+                return true;
+            }
+
+            let action = self.transition(state).get_action_of(tape.read());
+            let other_action = other
+                .transition(other_state)
+                .get_action_of(other_tape.read());
+            if (action.0, action.1) != (other_action.0, other_action.1) {
+                return false;
+            }
+
+            state = self.step(state, &mut tape);
+            other_state = other.step(other_state, &mut other_tape);
+        }
+
+        true
+    }
+
+    /// Performs a single step of the machine in the given state and returns
+    /// the new state.
+    #[inline]
+    fn step(&self, state: State, tape: &mut Tape) -> State {
+        let (symbol, direction, new_state) =
+            self.transitions[state as usize - 1].get_action_of(tape.read());
+
+        tape.write(symbol);
+        tape.move_head(direction);
+        new_state
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps.
+    /// Returns `Some(productivity)` if the machine did halt and `None` otherwise.
+    #[inline]
+    pub fn run_ones(&self, max_steps: usize) -> Option<usize> {
+        self.run(max_steps).map(|outcome| outcome.ones)
+    }
+
+    /// Runs the turing machine on the blank input for a maximum number of steps
+    /// and returns the number of steps performed when a 1 is first written.
+    /// Returns `None` if no 1 is written within the budget or before halting.
+    #[must_use]
+    pub fn steps_to_first_write(&self, max_steps: usize) -> Option<usize> {
+        let mut current_state = State::start();
+        let mut tape = Tape::new();
+
+        for i in 0..max_steps {
+            if current_state.is_halting() {
+                break;
+            }
+
+            let (symbol, direction, new_state) =
+                self.transitions[current_state as usize - 1].get_action_of(tape.read());
+            if symbol == 1 {
+                return Some(i + 1);
+            }
+
+            tape.write(symbol);
+            tape.move_head(direction);
+            current_state = new_state;
+        }
+
+        None
+    }
+
+    /// Compares the number of ones written by both machines when run for at
+    /// most `max_steps` steps, machines not halting within the budget being
+    /// the lowest.
+    #[must_use]
+    pub fn cmp_by_ones(&self, other: &Self, max_steps: usize) -> Ordering {
+        self.run_ones(max_steps).cmp(&other.run_ones(max_steps))
+    }
+
+    /// Compares the number of steps performed by both machines when run for at
+    /// most `max_steps` steps, machines not halting within the budget being
+    /// the lowest.
+    #[must_use]
+    pub fn cmp_by_steps(&self, other: &Self, max_steps: usize) -> Ordering {
+        let steps = |machine: &Self| machine.run(max_steps).map(|outcome| outcome.steps);
+
+        steps(self).cmp(&steps(other))
+    }
+}
+
+/// Formats the machine as its actions separated by spaces, or with `{:#}` as a
+/// table with a header row of the read symbols and one row per state.
+impl<const N: usize> Display for TuringMachine<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "   0   1")?;
+            for (t, state) in self.transitions.iter().zip(non_halting_states(N)) {
+                write!(f, "\n{} {}", state.to_str(), t)?;
+            }
+
+            return Ok(());
+        }
+
+        let mut transitions = self.transitions.iter();
+        write!(f, "{}", transitions.next().unwrap())?;
+
+        for t in transitions {
+            write!(f, " {}", t)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<const N: usize> From<PartialTuringMachine<N>> for TuringMachine<N> {
+    #[inline]
+    fn from(machine: PartialTuringMachine<N>) -> Self {
+        machine.into_complete()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> From<TuringMachine<N>> for Vec<Transition> {
+    #[inline]
+    fn from(machine: TuringMachine<N>) -> Self {
+        machine.transitions.to_vec()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> TryFrom<Vec<Transition>> for TuringMachine<N> {
+    type Error = ParseError;
+
+    fn try_from(transitions: Vec<Transition>) -> Result<Self, Self::Error> {
+        let found = transitions.len();
+        let transitions = transitions
+            .try_into()
+            .map_err(|_| ParseError::StateCount { expected: N, found })?;
+
+        Ok(Self::try_new(transitions)?)
+    }
+}
+
+/// Parses an action written as `<symbol><direction><state>` executed when the
+/// given symbol is read.
+fn parse_action(token: &str, read: u8) -> Result<Action, ParseError> {
+    if token == "---" {
+        return Ok(Action::new(read, Direction::Right, State::Halt));
+    }
+
+    Action::try_from(token)
+}
+
+/// Formats an unpacked action as `<symbol><direction><state>` using `Z` for
+/// the halting state.
+fn format_action((symbol, direction, state): (u8, Direction, State)) -> String {
+    let direction = match direction {
+        Direction::Left => 'L',
+        Direction::Right => 'R',
+    };
+    format!("{}{}{}", symbol, direction, state.letter())
+}
+
+/// Error returned when a transition table has an action going to a state
+/// greater than the number of states of the machine.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidMachine {
+    /// State of the transition holding the invalid action.
+    pub state: State,
+    /// Symbol on which the invalid action is executed.
+    pub symbol: u8,
+    /// State the invalid action goes to.
+    pub target: State,
+}
+
+impl Display for InvalidMachine {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "transition for state {} on symbol {} goes to unknown state {}",
+            self.state.to_str(),
+            self.symbol,
+            self.target.to_str()
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for InvalidMachine {}
+
+impl From<InvalidMachine> for ParseError {
+    #[inline]
+    fn from(error: InvalidMachine) -> Self {
+        Self::InvalidState(error.target.to_str().to_string())
+    }
+}
+
+/// Error returned when decoding a machine from bytes fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The number of bytes is not twice the number of states.
+    InvalidLength {
+        /// Expected number of bytes.
+        expected: usize,
+        /// Number of bytes found.
+        found: usize,
+    },
+    /// A byte does not encode an action as its state field is not a state.
+    InvalidAction {
+        /// Index of the byte.
+        index: usize,
+        /// Value of the byte.
+        byte: u8,
+    },
+    /// An action goes to a state greater than the number of states.
+    InvalidMachine(InvalidMachine),
+}
+
+impl Display for DecodeError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength { expected, found } => {
+                write!(f, "expected {} bytes, found {}", expected, found)
+            }
+            Self::InvalidAction { index, byte } => {
+                write!(f, "invalid action {:#04x} at byte {}", byte, index)
+            }
+            Self::InvalidMachine(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for DecodeError {}
+
+impl From<InvalidMachine> for DecodeError {
+    #[inline]
+    fn from(error: InvalidMachine) -> Self {
+        Self::InvalidMachine(error)
+    }
+}
+
+/// Error returned when decoding a machine from a record of the bbchallenge
+/// seed database fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DbError {
+    /// The number of states of the record is not the one of the machine.
+    StateCount {
+        /// Number of states of the machine.
+        expected: usize,
+        /// Number of states of the record.
+        found: usize,
+    },
+    /// The record does not hold 6 bytes per state.
+    InvalidLength {
+        /// Expected number of bytes.
+        expected: usize,
+        /// Number of bytes found.
+        found: usize,
+    },
+    /// A byte is not a valid symbol, direction or state.
+    InvalidByte {
+        /// Index of the byte in the record.
+        index: usize,
+        /// Value of the byte.
+        byte: u8,
+    },
+    /// A transition goes to a state greater than the number of states.
+    InvalidMachine(InvalidMachine),
+}
+
+impl Display for DbError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StateCount { expected, found } => {
+                write!(f, "expected {} states, found {}", expected, found)
+            }
+            Self::InvalidLength { expected, found } => {
+                write!(f, "expected {} bytes, found {}", expected, found)
+            }
+            Self::InvalidByte { index, byte } => {
+                write!(f, "invalid byte {} at index {}", byte, index)
+            }
+            Self::InvalidMachine(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for DbError {}
+
+impl From<InvalidMachine> for DbError {
+    #[inline]
+    fn from(error: InvalidMachine) -> Self {
+        Self::InvalidMachine(error)
+    }
+}
+
+/// Error returned when a bounded run needs more tape than allowed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TapeLimitExceeded {
+    /// Maximum number of cells the tape was allowed to allocate.
+    pub max_cells: usize,
+    /// Number of steps performed when the limit was exceeded.
+    pub step: usize,
+}
+
+impl Display for TapeLimitExceeded {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tape exceeded {} cells at step {}",
+            self.max_cells, self.step
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for TapeLimitExceeded {}
+
+/// Error returned when a machine falls off the left end of a one-way tape.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FellOffTape {
+    /// State of the machine when it moved left of position 0.
+    pub state: State,
+    /// Number of steps performed, including the one falling off the tape.
+    pub step: usize,
+}
+
+impl Display for FellOffTape {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "fell off the left end of the tape in state {} at step {}",
+            self.state.to_str(),
+            self.step
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for FellOffTape {}
+
+/// Error returned when a partial machine reaches an undefined transition.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UndefinedTransition {
+    /// State of the machine when the transition was reached.
+    pub state: State,
+    /// Symbol read when the transition was reached.
+    pub symbol: u8,
+    /// Number of steps performed before the transition was reached.
+    pub step: usize,
+    /// Position of the head when the transition was reached.
+    pub head: isize,
+}
+
+impl Display for UndefinedTransition {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "undefined transition for state {} on symbol {} at step {} (head at {})",
+            self.state.to_str(),
+            self.symbol,
+            self.step,
+            self.head
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for UndefinedTransition {}
+
+/// A binary-alphabet partial Turing Machine with N non-halting states
+#[derive(Debug, Clone)]
+pub struct PartialTuringMachine<const N: usize> {
+    transitions: [PartialTransition; N],
+}
+
+impl<const N: usize> PartialTuringMachine<N> {
+    /// Creates a new partial Turing Machine
+    ///
+    /// # Panics
+    /// Panics if `N == 0` or `N > State::MAX_STATES`.
+    #[inline]
+    #[must_use]
+    pub fn new(transitions: [PartialTransition; N]) -> Self {
+        assert!(N >= 1 && N <= State::MAX_STATES);
+
+        Self { transitions }
+    }
+
+    /// Adds the given transition to the `PartialTuringMachine`
+    #[inline]
+    pub fn add_transition(&mut self, state: State, symbol: u8, action: Action) {
+        self.transitions[state as usize - 1].set_action_of(symbol, Some(action));
+    }
+
+    /// Completes the machine, every unspecified action writing back the symbol
+    /// read, moving right and going to the halting state.
+    ///
+    /// # Panics
+    /// Panics if `N == 0` or `N > State::MAX_STATES`.
+    #[must_use]
+    pub fn into_complete(self) -> TuringMachine<N> {
+        TuringMachine::new(
+            self.transitions
+                .iter()
+                .map(|&transition| transition.into())
+                .collect::<Vec<Transition>>()
+                .try_into()
+                .unwrap(),
+        )
+    }
+
+    /// Checks if the machine is N-state full, that is if every state has at
+    /// least one specified transition.
+    ///
+    /// Unlike [`PartialTuringMachine::is_complete`] it does not require every
+    /// transition to be specified.
+    #[inline]
+    #[must_use]
+    fn is_n_state_full(&self) -> bool {
+        self.transitions
+            .iter()
+            .filter(|t| t.count_specified_actions() > 0)
+            .count()
+            .eq(&N)
+    }
+
+    /// Checks if the machine contains `N` transitions that moves the head to
+    /// the right when a zero is read with the given transition
+    #[must_use]
+    fn is_0_dextrous_with(&self, state: State, symbol: u8, action: Action) -> bool {
+        self.transitions
+            .iter()
+            .zip(non_halting_states(N))
+            .filter(|&(t, s)| {
+                let action_on_0 = if s == state && symbol == 0 {
+                    Some(action)
+                } else {
+                    t.action(0)
+                };
+
+                action_on_0.map_or(false, |a| a.get_direction() == Direction::Right)
+            })
+            .count()
+            .eq(&N)
+    }
+
+    /// Counts the number of specified transitions of the machine.
+    #[must_use]
+    pub fn count_specified_transitions(&self) -> usize {
+        self.transitions
+            .iter()
+            .map(|t| t.count_specified_actions())
+            .sum()
+    }
+
+    /// Checks if the machine is complete, that is if all of its `2 * N`
+    /// transitions are specified.
+    ///
+    /// A complete machine is N-state full but an N-state full machine may
+    /// still have undefined transitions.
+    #[inline]
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.count_specified_transitions() == 2 * N
+    }
+
+    /// Cheaply checks whether the machine obviously never halts on the blank
+    /// tape.
+    ///
+    /// Starting from state A, the transitions on 0 are followed as long as
+    /// they all move the head in the same direction. Each of them then reads a
+    /// blank cell never visited before, so reaching a state twice means the
+    /// machine runs away forever. Returns `false` when in doubt.
+    #[must_use]
+    pub fn quick_reject(&self) -> bool {
+        let mut seen = [false; N];
+        let mut state = State::start();
+        let mut direction = None;
+
+        while !state.is_halting() {
+            if seen[state as usize - 1] {
+                return true;
+            }
+            seen[state as usize - 1] = true;
+
+            match self.transitions[state as usize - 1].action(0) {
+                Some(action) if direction.map_or(true, |d| d == action.get_direction()) => {
+                    direction = Some(action.get_direction());
+                    state = action.next_state();
+                }
+                _ => return false,
+            }
+        }
+
+        false
+    }
+
+    /// Returns the highest state used by the machine, either as the starting
+    /// state or as the target of a specified transition.
+    #[must_use]
+    pub(crate) fn highest_used_state(&self) -> State {
+        let highest = self
+            .transitions
+            .iter()
+            .flat_map(|t| (0..2).filter_map(move |symbol| t.action(symbol)))
+            .map(Action::next_state)
+            .filter(|state| !state.is_halting())
+            .map(|state| state as u8)
+            .fold(State::start() as u8, u8::max);
+
+        State::from(highest)
+    }
+
+    /// Gets the first state with unspecified transitions or if none the last state.
+    #[must_use]
+    fn state_choice_limit(&self) -> State {
+        non_halting_states(N)
+            .zip(self.transitions.iter())
+            .rev()
+            .find(|(_, t)| t.count_specified_actions() > 0)
+            .map_or(State::Halt, |(s, _)| s)
+    }
+
+    /// Returns the highest state an undefined transition of `state` may go to
+    /// without skipping a state, that is the first unused state or the last
+    /// state if every state is used.
+    ///
+    /// `state` counts as used since its transition is being defined.
+    #[must_use]
+    fn next_state_limit(&self, state: State) -> State {
+        let used = self.state_choice_limit().index().max(state.index());
+
+        non_halting_states((used + 1).min(N)).last().unwrap()
+    }
+
+    /// Runs the `PartialTuringMachine` on a blank tape
+    /// Returns `Ok(Some(productivity))` if the machine did halt and `Ok(None)`
+    /// if it did not within `max_steps` steps.
+    ///
+    /// # Errors
+    /// Returns an `UndefinedTransition` error if the machine reaches a
+    /// transition which is not specified.
+    #[inline]
+    pub fn run(&self, max_steps: usize) -> Result<Option<usize>, UndefinedTransition> {
+        self.run_from_tape(Tape::new(), max_steps)
+    }
+
+    /// Runs the `PartialTuringMachine` like [`PartialTuringMachine::run`] on a
+    /// tape holding `initial` from position 0 onwards, the head starting at
+    /// position `head`. Cells outside of `initial` are blank.
+    ///
+    /// # Errors
+    /// Returns an `UndefinedTransition` error if the machine reaches a
+    /// transition which is not specified.
+    ///
+    /// # Panics
+    /// Panics in `debug` mode if `initial` holds a symbol other than 0 or 1.
+    #[inline]
+    pub fn run_from(
+        &self,
+        initial: &[u8],
+        head: isize,
+        max_steps: usize,
+    ) -> Result<Option<usize>, UndefinedTransition> {
+        self.run_from_tape(Tape::from_symbols(initial, head), max_steps)
+    }
+
+    /// Runs the `PartialTuringMachine` on the given tape from the start state
+    /// and the current head position.
+    fn run_from_tape(
+        &self,
+        mut tape: Tape,
+        max_steps: usize,
+    ) -> Result<Option<usize>, UndefinedTransition> {
+        let mut current_state = State::start();
+
+        let mut i = 0;
+        while !current_state.is_halting() && i < max_steps {
+            let (symbol, direction, new_state) = self.transitions[current_state as usize - 1]
+                .get_action_of(tape.read())
+                .ok_or_else(|| UndefinedTransition {
+                    state: current_state,
+                    symbol: tape.read(),
+                    step: i,
+                    head: tape.head(),
+                })?;
+
+            tape.write(symbol);
+            tape.move_head(direction);
+            current_state = new_state;
+            i += 1;
+        }
+
+        Ok(current_state.is_halting().then(|| tape.count_ones()))
+    }
+
+    /// Runs the `PartialTuringMachine` on a blank tape, an undefined
+    /// transition acting as a transition to the halting state.
+    /// Returns `Some(outcome)` if the machine did halt and `None` otherwise.
+    ///
+    /// Reaching an undefined transition counts as a halting step: the symbol
+    /// read is written back and the head moves right, as with
+    /// [`PartialTuringMachine::into_complete`].
+    #[inline]
+    #[must_use]
+    pub fn run_halt_on_undefined(&self, max_steps: usize) -> Option<RunOutcome> {
+        self.clone().into_complete().run(max_steps)
+    }
+}
+
+impl<const N: usize> Display for PartialTuringMachine<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut transitions = self.transitions.iter();
+        write!(f, "{}", transitions.next().unwrap())?;
+
+        for t in transitions {
+            write!(f, " {}", t)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Generates a random `N`-state busy beaver candidate halting within
+/// `max_steps` steps.
+///
+/// # Panics
+/// Panics if `N == 0` or `N > State::MAX_STATES`.
+#[cfg(feature = "thread-rng")]
+#[must_use]
+pub fn generate_busy_beaver<const N: usize>(max_steps: usize) -> TuringMachine<N> {
+    generate_busy_beaver_seeded(max_steps, &mut thread_rng())
+}
+
+/// Generates a random `N`-state busy beaver candidate halting within
+/// `max_steps` steps using the given random number generator.
+///
+/// The machine is built incrementally: it is run on the blank tape and a
+/// random action is added each time an undefined transition is reached.
+/// Candidates which do not halt within `max_steps` are discarded.
+///
+/// With the `log` feature, discarded and accepted candidates are reported at
+/// the debug level.
+///
+/// This function does not need the default `thread-rng` feature so it can be
+/// used where no OS entropy is available, e.g. on `wasm32-unknown-unknown`,
+/// with a generator seeded from JavaScript:
+///
+/// ```
+/// use busy_beaver::turing_machine::generate_busy_beaver_seeded;
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+///
+/// // e.g. a seed drawn with `crypto.getRandomValues` and passed from JavaScript
+/// let seed = 42;
+/// let tm = generate_busy_beaver_seeded::<3, _>(100, &mut StdRng::seed_from_u64(seed));
+///
+/// assert!(tm.run(100).is_some());
+/// ```
+///
+/// # Panics
+/// Panics if `N == 0` or `N > State::MAX_STATES`.
+pub fn generate_busy_beaver_seeded<const N: usize, R: Rng + ?Sized>(
+    max_steps: usize,
+    rng: &mut R,
+) -> TuringMachine<N> {
+    generate_busy_beaver_with_config(max_steps, &GeneratorConfig::default(), rng)
+}
+
+/// Parameters of the random generation of busy beaver candidates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GeneratorConfig<'a> {
+    /// Probability that a newly added transition halts once every state is
+    /// used. The last undefined transition always halts.
+    ///
+    /// With `None` the probability is one over the number of undefined
+    /// transitions, so that it increases as the machine is completed.
+    pub halt_probability: Option<f64>,
+    /// Probability that a newly added non-halting transition writes a 1,
+    /// 0.5 by default.
+    pub symbol_one_probability: f64,
+    /// Input the candidates are run on during the generation, written from
+    /// position 0 onwards with the head starting at position 0 as with
+    /// [`TuringMachine::run_from`]. Empty, i.e. the blank input, by default.
+    pub input: &'a [u8],
+}
+
+impl Default for GeneratorConfig<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            halt_probability: None,
+            symbol_one_probability: 0.5,
+            input: &[],
+        }
+    }
+}
+
+/// Generates a random `N`-state busy beaver candidate halting within
+/// `max_steps` steps like [`generate_busy_beaver_seeded`] with the given
+/// generation parameters.
+///
+/// Biasing the generation away from halting transitions favors longer running
+/// candidates.
+///
+/// With a non-blank input, the candidates are run on that input and halt on it
+/// within `max_steps` steps, but may not halt on the blank input. The
+/// shortcuts only valid on the blank input, i.e. starting by writing a 1 and
+/// moving right and [`PartialTuringMachine::quick_reject`], are then skipped.
+///
+/// # Panics
+/// Panics if `N == 0` or `N > State::MAX_STATES` or if the halt probability or
+/// the probability of writing a 1 is not between 0 and 1.
+/// Panics in `debug` mode if the input holds a symbol other than 0 or 1.
+pub fn generate_busy_beaver_with_config<const N: usize, R: Rng + ?Sized>(
+    max_steps: usize,
+    config: &GeneratorConfig<'_>,
+    rng: &mut R,
+) -> TuringMachine<N> {
+    assert!(
+        (0.0..=1.0).contains(&config.symbol_one_probability),
+        "the probability of writing a 1 must be between 0 and 1"
+    );
+    assert!(
+        config
+            .halt_probability
+            .into_iter()
+            .all(|probability| (0.0..=1.0).contains(&probability)),
+        "the halt probability must be between 0 and 1"
+    );
+
+    let blank = config.input.iter().all(|&symbol| symbol == 0);
+
+    loop {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); N]);
+        // Writing a 1 and moving right to a new state first loses no generality
+        // up to symmetry. A single state machine only halts if it halts at once.
+        if blank {
+            let next_state = match machine.next_state_limit(State::A) {
+                State::A => State::Halt,
+                state => state,
+            };
+            machine.add_transition(State::A, 0, Action::new(1, Direction::Right, next_state));
+        }
+
+        loop {
+            if blank && machine.quick_reject() {
+                #[cfg(feature = "log")]
+                log::debug!("rejected candidate {}", machine);
+                break;
+            }
+
+            match machine.run_from(config.input, 0, max_steps) {
+                Ok(Some(_)) => {
+                    #[cfg(feature = "log")]
+                    log::debug!("accepted candidate {}", machine);
+                    return machine.into_complete();
+                }
+                Ok(None) => {
+                    #[cfg(feature = "log")]
+                    log::debug!("discarded candidate {}", machine);
+                    break;
+                }
+                Err(UndefinedTransition { state, symbol, .. }) => {
+                    let action = random_action(&machine, state, symbol, config, rng);
+                    machine.add_transition(state, symbol, action);
+                }
+            }
+        }
+    }
+}
+
+/// Returns an endless iterator over random `N`-state busy beaver candidates
+/// halting within `max_steps` steps.
+///
+/// Each candidate is generated as with [`generate_busy_beaver`].
+///
+/// # Panics
+/// Panics when iterated if `N == 0` or `N > State::MAX_STATES`.
+#[cfg(feature = "thread-rng")]
+pub fn busy_beaver_stream<const N: usize>(
+    max_steps: usize,
+) -> impl Iterator<Item = TuringMachine<N>> {
+    std::iter::repeat_with(move || generate_busy_beaver(max_steps))
+}
+
+/// Draws a random action for the undefined transition of the given state and
+/// symbol.
+///
+/// Once every state is used, the action may halt with the probability given by
+/// the configuration, the last undefined transition always halting.
+fn random_action<const N: usize, R: Rng + ?Sized>(
+    machine: &PartialTuringMachine<N>,
+    state: State,
+    symbol: u8,
+    config: &GeneratorConfig<'_>,
+    rng: &mut R,
+) -> Action {
+    let undefined_transitions = 2 * N - machine.count_specified_transitions();
+    let halts = machine.is_n_state_full()
+        && match config.halt_probability {
+            Some(probability) => undefined_transitions == 1 || rng.gen_bool(probability),
+            None => rng.gen_range(0..undefined_transitions) == 0,
+        };
+    if halts {
+        return Action::new(1, Direction::random_with(rng), State::Halt);
+    }
+
+    let next_state = random_state_in(State::A..=machine.next_state_limit(state), rng);
+
+    let written = u8::from(rng.gen_bool(config.symbol_one_probability));
+    let mut direction = Direction::random_with(rng);
+    // A machine moving right on every 0 runs away once past its rightmost 1
+    if machine.is_0_dextrous_with(state, symbol, Action::new(written, direction, next_state)) {
+        direction = Direction::Left;
+    }
+
+    Action::new(written, direction, next_state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enumeration::enumerate_machines;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::{HashMap, HashSet};
+
+    fn random_machine<const N: usize>(rng: &mut StdRng) -> TuringMachine<N> {
+        let mut random_action = || {
+            Action::new(
+                rng.gen_range(0..=1),
+                Direction::random_with(rng),
+                State::random_with(State::Halt..=State::from(N as u8), rng),
+            )
+        };
+
+        TuringMachine::new(
+            (0..N)
+                .map(|_| Transition::new(random_action(), random_action()))
+                .collect::<Vec<Transition>>()
+                .try_into()
+                .unwrap(),
+        )
+    }
+
+    fn bb2_champion() -> TuringMachine<2> {
+        TuringMachine::new([
+            Transition::new(
+                Action::new(1, Direction::Right, State::B),
+                Action::new(1, Direction::Left, State::B),
+            ),
+            Transition::new(
+                Action::new(1, Direction::Left, State::A),
+                Action::new(1, Direction::Right, State::Halt),
+            ),
+        ])
+    }
+
+    fn three_state_machine() -> TuringMachine<3> {
+        TuringMachine::new([
+            Transition::new(
+                Action::new(1, Direction::Right, State::B),
+                Action::new(1, Direction::Right, State::Halt),
+            ),
+            Transition::new(
+                Action::new(0, Direction::Right, State::C),
+                Action::new(1, Direction::Right, State::B),
+            ),
+            Transition::new(
+                Action::new(1, Direction::Left, State::C),
+                Action::new(1, Direction::Left, State::A),
+            ),
+        ])
+    }
+
+    #[test]
+    fn clone_runs_identically() {
+        let tm = three_state_machine();
+        let cloned = tm.clone();
+
+        assert_eq!(tm.run(100), cloned.run(100));
+        assert_eq!(tm.to_string(), cloned.to_string());
+    }
+
+    #[test]
+    fn run_immediate_halt() {
+        let tm = TuringMachine::new([
+            Transition::new(
+                Action::new(1, Direction::Right, State::Halt),
+                Action::new(1, Direction::Right, State::Halt),
+            ),
+            Transition::new(
+                Action::new(1, Direction::Right, State::Halt),
+                Action::new(1, Direction::Right, State::Halt),
+            ),
+        ]);
+
+        assert_eq!(
+            tm.run(10),
+            Some(RunOutcome {
+                steps: 1,
+                ones: 1,
+                space: 2,
+                halt_state: State::Halt,
+                writes_of: [0, 1],
+            })
+        );
+    }
+
+    #[test]
+    fn run_bb2_champion() {
+        let tm = bb2_champion();
+
+        assert_eq!(
+            tm.run(100),
+            Some(RunOutcome {
+                steps: 6,
+                ones: 4,
+                space: 4,
+                halt_state: State::Halt,
+                writes_of: [0, 6],
+            })
+        );
+        assert_eq!(tm.run_ones(100), Some(4));
+    }
+
+    #[test]
+    fn run_exceeds_max_steps() {
+        let tm = bb2_champion();
+
+        assert_eq!(tm.run(5), None);
+        assert_eq!(
+            tm.run(6),
+            Some(RunOutcome {
+                steps: 6,
+                ones: 4,
+                space: 4,
+                halt_state: State::Halt,
+                writes_of: [0, 6],
+            })
+        );
+    }
+
+    #[test]
+    fn from_table_bb2_champion() {
+        let tm = TuringMachine::from_table([("1RB", "1LB"), ("1LA", "1RZ")]).unwrap();
+
+        assert_eq!(tm, bb2_champion());
+        assert_eq!(tm.run(100).map(|outcome| outcome.ones), Some(4));
+        assert_eq!(
+            TuringMachine::<2>::from_table([("1RB", "1LB"), ("1LA", "---")]),
+            Ok(tm)
+        );
+    }
+
+    #[test]
+    fn from_table_errors() {
+        assert_eq!(
+            TuringMachine::from_table([("1RB", "1LB"), ("1LA", "1R")]),
+            Err(ParseError::InvalidAction("1R".to_string()))
+        );
+        assert_eq!(
+            TuringMachine::from_table([("1RC", "1LB"), ("1LA", "1RZ")]),
+            Err(ParseError::InvalidState("C".to_string()))
+        );
+    }
+
+    #[test]
+    fn from_standard_text_round_trip() {
+        let tm = TuringMachine::<2>::from_standard_text("1RB1LB_1LA1RZ").unwrap();
+
+        assert_eq!(tm.to_string(), bb2_champion().to_string());
+        assert_eq!(tm.to_string(), "1RB 1LB 1LA 1RZ");
+        assert_eq!(
+            tm.run(100),
+            Some(RunOutcome {
+                steps: 6,
+                ones: 4,
+                space: 4,
+                halt_state: State::Halt,
+                writes_of: [0, 6],
+            })
+        );
+
+        let tm = TuringMachine::<3>::from_standard_text("1RB1RZ_0RC1RB_1LC1LA").unwrap();
+        assert_eq!(tm.to_string(), three_state_machine().to_string());
+    }
+
+    #[test]
+    fn display_alternate_table() {
+        let tm = three_state_machine();
+
+        assert_eq!(
+            format!("{:#}", tm),
+            "   0   1\nA 1RB 1RZ\nB 0RC 1RB\nC 1LC 1LA"
+        );
+        assert_eq!(format!("{}", tm), "1RB 1RZ 0RC 1RB 1LC 1LA");
+
+        let table = format!("{:#}", tm);
+        let lines = table.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1..].iter().all(|line| line.len() == 9));
+        assert!(lines[1..]
+            .iter()
+            .all(|line| line.match_indices(' ').map(|(i, _)| i).eq(vec![1, 5])));
+    }
+
+    #[test]
+    fn from_standard_text_halt_spellings() {
+        let tm = TuringMachine::<2>::from_standard_text("1RB1LB_1LA1R-").unwrap();
+        assert_eq!(tm.to_string(), "1RB 1LB 1LA 1RZ");
+
+        let tm = TuringMachine::<2>::from_standard_text("1RB1LB_1LA---").unwrap();
+        assert_eq!(tm.to_string(), "1RB 1LB 1LA 1RZ");
+    }
+
+    #[test]
+    fn from_standard_text_state_count_mismatch() {
+        assert_eq!(
+            TuringMachine::<3>::from_standard_text("1RB1LB_1LA1RZ").unwrap_err(),
+            ParseError::StateCount {
+                expected: 3,
+                found: 2
+            }
+        );
+    }
+
+    #[test]
+    fn from_standard_text_invalid_tokens() {
+        assert_eq!(
+            TuringMachine::<2>::from_standard_text("2RB1LB_1LA1RZ").unwrap_err(),
+            ParseError::InvalidSymbol("2".to_string())
+        );
+        assert_eq!(
+            TuringMachine::<2>::from_standard_text("1XB1LB_1LA1RZ").unwrap_err(),
+            ParseError::InvalidDirection("X".to_string())
+        );
+        assert_eq!(
+            TuringMachine::<2>::from_standard_text("1RB1LB_1LA1RQ").unwrap_err(),
+            ParseError::InvalidState("Q".to_string())
+        );
+        assert_eq!(
+            TuringMachine::<2>::from_standard_text("1RB1LB_1LA1R").unwrap_err(),
+            ParseError::InvalidBlock("1LA1R".to_string())
+        );
+    }
+
+    #[test]
+    fn to_standard_text() {
+        assert_eq!(bb2_champion().to_standard_text(), "1RB1LB_1LA1RZ");
+        assert_eq!(
+            three_state_machine().to_standard_text(),
+            "1RB1RZ_0RC1RB_1LC1LA"
+        );
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let bytes = bb2_champion().to_bytes();
+        assert_eq!(bytes.len(), 4);
+        assert_eq!(TuringMachine::<2>::from_bytes(&bytes), Ok(bb2_champion()));
+
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..100 {
+            let tm = random_machine::<4>(&mut rng);
+            let bytes = tm.to_bytes();
+
+            assert_eq!(bytes.len(), 8);
+            assert_eq!(TuringMachine::<4>::from_bytes(&bytes), Ok(tm));
+        }
+    }
+
+    #[test]
+    fn from_bytes_invalid() {
+        let bytes = three_state_machine().to_bytes();
+
+        assert_eq!(
+            TuringMachine::<3>::from_bytes(&bytes[..5]),
+            Err(DecodeError::InvalidLength {
+                expected: 6,
+                found: 5
+            })
+        );
+        assert_eq!(
+            TuringMachine::<2>::from_bytes(&bytes[..4]),
+            Err(DecodeError::InvalidMachine(InvalidMachine {
+                state: State::B,
+                symbol: 0,
+                target: State::C,
+            }))
+        );
+
+        let mut bytes = bb2_champion().to_bytes();
+        bytes[3] = 0xff;
+        assert_eq!(
+            TuringMachine::<2>::from_bytes(&bytes),
+            Err(DecodeError::InvalidAction {
+                index: 3,
+                byte: 0xff
+            })
+        );
+    }
+
+    #[test]
+    fn from_db_record() {
+        #[rustfmt::skip]
+        let record = [
+            1, 0, 2, 1, 1, 2,
+            1, 1, 1, 0, 0, 0,
+        ];
+        assert_eq!(
+            TuringMachine::<2>::from_db_record(&record, 2),
+            Ok(bb2_champion())
+        );
+
+        #[rustfmt::skip]
+        let record = [
+            1, 0, 2, 1, 1, 3,
+            1, 0, 3, 1, 0, 2,
+            1, 0, 4, 0, 1, 5,
+            1, 1, 1, 1, 1, 4,
+            0, 0, 0, 0, 1, 1,
+        ];
+        let bb5 = TuringMachine::<5>::from_db_record(&record, 5).unwrap();
+        assert_eq!(bb5.to_standard_text(), "1RB1LC_1RC1RB_1RD0LE_1LA1LD_0RZ0LA");
+    }
+
+    #[test]
+    fn from_db_record_invalid() {
+        let record = [1, 0, 2, 1, 1, 2, 1, 1, 1, 0, 0, 0];
+
+        assert_eq!(
+            TuringMachine::<3>::from_db_record(&record, 2),
+            Err(DbError::StateCount {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            TuringMachine::<2>::from_db_record(&record[..11], 2),
+            Err(DbError::InvalidLength {
+                expected: 12,
+                found: 11
+            })
+        );
+
+        let mut invalid = record;
+        invalid[4] = 2;
+        assert_eq!(
+            TuringMachine::<2>::from_db_record(&invalid, 2),
+            Err(DbError::InvalidByte { index: 4, byte: 2 })
+        );
+
+        let mut invalid = record;
+        invalid[8] = 3;
+        assert_eq!(
+            TuringMachine::<2>::from_db_record(&invalid, 2),
+            Err(DbError::InvalidMachine(InvalidMachine {
+                state: State::B,
+                symbol: 0,
+                target: State::C,
+            }))
+        );
+    }
+
+    fn assert_standard_text_round_trip<const N: usize>() {
+        let mut rng = StdRng::seed_from_u64(N as u64);
+        for _ in 0..100 {
+            let tm = random_machine::<N>(&mut rng);
+            let text = tm.to_standard_text();
+            let parsed = TuringMachine::<N>::from_standard_text(&text).unwrap();
+
+            assert_eq!(parsed.to_standard_text(), text);
+            assert_eq!(parsed.to_string(), tm.to_string());
+        }
+    }
+
+    #[test]
+    fn standard_text_round_trip() {
+        assert_standard_text_round_trip::<2>();
+        assert_standard_text_round_trip::<3>();
+        assert_standard_text_round_trip::<4>();
+        assert_standard_text_round_trip::<5>();
+    }
+
+    #[test]
+    fn detect_cycle_halting() {
+        assert_eq!(
+            bb2_champion().detect_cycle(100),
+            CycleResult::Halted(RunOutcome {
+                steps: 6,
+                ones: 4,
+                space: 4,
+                halt_state: State::Halt,
+                writes_of: [0, 6],
+            })
+        );
+    }
+
+    #[test]
+    fn detect_cycle_cycling() {
+        let tm = TuringMachine::<2>::from_standard_text("1RB0RB_0LA1RZ").unwrap();
+
+        assert_eq!(
+            tm.detect_cycle(100),
+            CycleResult::Cycles {
+                start: 0,
+                period: 4
+            }
+        );
+    }
+
+    #[test]
+    fn detect_cycle_matches_whole_tape_lookup() {
+        let whole_tape_lookup = |tm: &TuringMachine<2>, max_steps| {
+            let mut current_state = State::start();
+            let mut tape = Tape::new();
+            let mut seen = HashMap::new();
+
+            for i in 0..max_steps {
+                if current_state.is_halting() {
+                    return None;
+                }
+                if let Some(&start) = seen.get(&(current_state, tape.clone())) {
+                    return Some((start, i - start));
+                }
+                seen.insert((current_state, tape.clone()), i);
+                current_state = tm.step(current_state, &mut tape);
+            }
+
+            None
+        };
+
+        for tm in enumerate_machines::<2>().step_by(7) {
+            let expected = whole_tape_lookup(&tm, 50);
+            let found = match tm.detect_cycle(50) {
+                CycleResult::Cycles { start, period } => Some((start, period)),
+                _ => None,
+            };
+
+            assert_eq!(found, expected, "{}", tm);
+        }
+    }
+
+    #[test]
+    fn detect_cycle_translated_cycler() {
+        let tm = TuringMachine::<2>::from_standard_text("1RA1RZ_1RZ1RZ").unwrap();
+
+        assert_eq!(tm.detect_cycle(100), CycleResult::Unknown);
+        assert_eq!(tm.run(100), None);
+    }
+
+    #[test]
+    fn run_matches_naive_loop() {
+        let tm = TuringMachine::<4>::from_standard_text("0LB1RC_1RA1LB_1RB0RD_0RZ0RA").unwrap();
+
+        for max_steps in 0..100 {
+            assert_eq!(tm.run(max_steps), tm.run_with(max_steps, |_| {}));
+        }
+    }
+
+    #[test]
+    fn run_from_blank_matches_run() {
+        let tm = TuringMachine::<2>::from_standard_text("1RB1LB_1LA1RZ").unwrap();
+
+        assert_eq!(tm.run_from(&[], 0, 100), tm.run(100));
+        assert_eq!(tm.run_from(&[0, 0, 0], 1, 100), tm.run(100));
+    }
+
+    #[test]
+    fn run_from_non_blank_tape() {
+        let tm = TuringMachine::<2>::from_standard_text("1RB1LB_1LA1RZ").unwrap();
+
+        assert_eq!(
+            tm.run_from(&[1, 1], 1, 100),
+            Some(RunOutcome {
+                steps: 2,
+                ones: 2,
+                space: 2,
+                halt_state: State::Halt,
+                writes_of: [0, 2],
+            })
+        );
+        assert!(tm.run_from(&[1, 1], 1, 100).unwrap().steps < tm.run(100).unwrap().steps);
+    }
+
+    #[test]
+    fn run_from_head_outside_initial() {
+        let tm = TuringMachine::<2>::from_standard_text("1RB1LB_1LA1RZ").unwrap();
+
+        let blank = tm.run(100).unwrap();
+        let outcome = tm.run_from(&[1, 1], -200, 100).unwrap();
+
+        assert_eq!((outcome.steps, outcome.space), (blank.steps, blank.space));
+        assert_eq!(outcome.ones, blank.ones + 2);
+        assert_eq!(tm.run_from(&[1, 1], 300, 100), Some(outcome));
+    }
+
+    #[test]
+    fn run_on_reused_tape() {
+        let mut tape = Tape::new();
+        let tm = TuringMachine::<4>::from_standard_text("1RB1LB_1LA0LC_1RZ1LD_1RD0RA").unwrap();
+
+        assert_eq!(tm.run_on(&mut tape, 1000), tm.run(1000));
+        assert_eq!(
+            bb2_champion().run_on(&mut tape, 1000),
+            bb2_champion().run(1000)
+        );
+        assert_eq!(tm.run_on(&mut tape, 1000), tm.run(1000));
+    }
+
+    #[test]
+    fn run_on_compacted_tape() {
+        let tm = TuringMachine::<4>::from_standard_text("1RB1LB_1LA0LC_1RZ1LD_1RD0RA").unwrap();
+        let mut tape = Tape::new();
+        tape.compact();
+
+        assert_eq!(tm.run_on(&mut tape, 1000), tm.run(1000));
+        assert_eq!(
+            bb2_champion().run_on(&mut tape, 1000),
+            bb2_champion().run(1000)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "fell off")]
+    fn run_on_one_way_tape_falls_off() {
+        let _ = bb2_champion().run_on(&mut Tape::new_one_way(), 1000);
+    }
+
+    #[test]
+    fn run_resumable() {
+        let tm = TuringMachine::<4>::from_standard_text("1RB1LB_1LA0LC_1RZ1LD_1RD0RA").unwrap();
+        let mut config = Configuration::new();
+
+        assert_eq!(tm.run_resumable(&mut config, 100), RunStatus::ExceededSteps);
+        assert_eq!(config.step(), 100);
+        assert_eq!(tm.run(100), None);
+
+        let status = tm.run_resumable(&mut config, 100);
+        assert_eq!(status, RunStatus::Halted(tm.run(200).unwrap()));
+        assert_eq!(status, tm.run_resumable(&mut Configuration::new(), 200));
+        assert!(config.state().is_halting());
+        assert_eq!(tm.run_resumable(&mut config, 100), status);
+    }
+
+    #[test]
+    fn run_resumable_matches_run() {
+        for machine in enumerate_machines::<2>().step_by(7) {
+            let mut config = Configuration::new();
+            let mut status = RunStatus::ExceededSteps;
+            for _ in 0..4 {
+                status = machine.run_resumable(&mut config, 5);
+            }
+
+            let mut once = Configuration::new();
+            assert_eq!(machine.run_resumable(&mut once, 20), status);
+            assert_eq!(once, config);
+            assert_eq!(
+                machine.run(20),
+                match status {
+                    RunStatus::Halted(outcome) => Some(outcome),
+                    _ => None,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn run_on_cell_widths() {
+        let tm = TuringMachine::<4>::from_standard_text("1RB1LB_1LA0LC_1RZ1LD_1RD0RA").unwrap();
+        let outcome = tm.run(1000);
+
+        assert_eq!(tm.run_on(&mut PackedTape::<u8>::new(), 1000), outcome);
+        assert_eq!(tm.run_on(&mut PackedTape::<u32>::new(), 1000), outcome);
+        assert_eq!(tm.run_on(&mut PackedTape::<u128>::new(), 1000), outcome);
+    }
+
+    #[test]
+    fn run_long_uniform_runs() {
+        // Shifts over long runs of ones in states B and D
+        let tm =
+            TuringMachine::<5>::from_standard_text("1RB1LC_1RC1RB_1RD0LE_1LA1LD_1RZ0LA").unwrap();
+
+        assert_eq!(
+            tm.run(50_000_000),
+            Some(RunOutcome {
+                steps: 47_176_870,
+                ones: 4098,
+                space: 12_289,
+                halt_state: State::Halt,
+                writes_of: [20_405, 47_156_465],
+            })
+        );
+        assert_eq!(tm.run(50_000_000), tm.run_accelerated(50_000_000));
+    }
+
+    #[test]
+    fn run_matches_naive_loop_exhaustive() {
+        for tm in enumerate_machines::<2>() {
+            assert_eq!(tm.run(50), tm.run_with(50, |_| {}));
+        }
+    }
+
+    #[test]
+    fn run_bounded_halting() {
+        assert_eq!(
+            bb2_champion().run_bounded(100, 2),
+            Ok(bb2_champion().run(100))
+        );
+    }
+
+    #[test]
+    fn run_bounded_exceeds_limit() {
+        let tm = TuringMachine::<2>::from_standard_text("1RA1RZ_1RZ1RZ").unwrap();
+
+        // The third cell is allocated when the head leaves position 63
+        assert_eq!(
+            tm.run_bounded(1000, 2),
+            Err(TapeLimitExceeded {
+                max_cells: 2,
+                step: 64
+            })
+        );
+        assert_eq!(tm.run_bounded(50, 2), Ok(None));
+    }
+
+    #[test]
+    fn run_one_way_falls_off() {
+        assert_eq!(
+            bb2_champion().run_one_way(100),
+            Err(FellOffTape {
+                state: State::A,
+                step: 3
+            })
+        );
+    }
+
+    #[test]
+    fn run_one_way_halting() {
+        let tm = TuringMachine::<2>::from_standard_text("1RB1RZ_0LA1RZ").unwrap();
+
+        assert_eq!(
+            tm.run_one_way(100),
+            Ok(Some(RunOutcome {
+                steps: 3,
+                ones: 1,
+                space: 2,
+                halt_state: State::Halt,
+                writes_of: [1, 2],
+            }))
+        );
+        assert_eq!(tm.run_one_way(2), Ok(None));
+    }
+
+    #[test]
+    fn proves_nonhalting_backward() {
+        // The halting transition B1 is only reachable from a 1 left of the head
+        let tm = TuringMachine::<2>::from_standard_text("1RB0RB_0LA1RZ").unwrap();
+        assert!(tm.proves_nonhalting_backward(10));
+
+        let tm = TuringMachine::<2>::from_standard_text("1RB1LB_1LA1LA").unwrap();
+        assert!(tm.proves_nonhalting_backward(0));
+    }
+
+    #[test]
+    fn proves_nonhalting_backward_unsure() {
+        assert!(!bb2_champion().proves_nonhalting_backward(10));
+        assert!(!three_state_machine().proves_nonhalting_backward(10));
+
+        let tm = TuringMachine::<2>::from_standard_text("1RA1RZ_1RZ1RZ").unwrap();
+        assert!(!tm.proves_nonhalting_backward(10));
+    }
+
+    #[test]
+    fn proves_nonhalting_backward_is_sound() {
+        for tm in enumerate_machines::<2>() {
+            if tm.proves_nonhalting_backward(6) {
+                assert_eq!(tm.run(100), None);
+            }
+        }
+    }
+
+    #[test]
+    fn run_status_halting() {
+        assert_eq!(
+            bb2_champion().run_status(100),
+            RunStatus::Halted(RunOutcome {
+                steps: 6,
+                ones: 4,
+                space: 4,
+                halt_state: State::Halt,
+                writes_of: [0, 6],
+            })
+        );
+    }
+
+    #[test]
+    fn run_status_cycling() {
+        let tm = TuringMachine::<2>::from_standard_text("0RB1RZ_0LA1RZ").unwrap();
+
+        assert_eq!(tm.run_status(100), RunStatus::Cycles { period: 2 });
+    }
+
+    #[test]
+    fn run_status_exceeded_steps() {
+        assert_eq!(bb2_champion().run_status(5), RunStatus::ExceededSteps);
+
+        let tm = TuringMachine::<2>::from_standard_text("1RA1RZ_1RZ1RZ").unwrap();
+        assert_eq!(tm.run_status(100), RunStatus::ExceededSteps);
+    }
+
+    #[test]
+    fn detect_translated_cycler() {
+        let tm = TuringMachine::<2>::from_standard_text("1RA1RZ_1RZ1RZ").unwrap();
+        for window in 0..3 {
+            assert_eq!(
+                tm.detect_translated_cycler(100, window),
+                Some(TranslatedCycle {
+                    start: 1,
+                    period: 1,
+                    offset: 1
+                })
+            );
+        }
+
+        let tm = TuringMachine::<2>::from_standard_text("1RB1RZ_0RA1RZ").unwrap();
+        assert_eq!(
+            tm.detect_translated_cycler(100, 2),
+            Some(TranslatedCycle {
+                start: 1,
+                period: 2,
+                offset: 2
+            })
+        );
+    }
+
+    #[test]
+    fn detect_translated_cycler_left() {
+        let tm = TuringMachine::<2>::from_standard_text("1LB1RZ_0LA1RZ").unwrap();
+
+        assert_eq!(
+            tm.detect_translated_cycler(100, 2),
+            Some(TranslatedCycle {
+                start: 1,
+                period: 2,
+                offset: -2
+            })
+        );
+    }
+
+    #[test]
+    fn detect_translated_cycler_halting() {
+        assert_eq!(bb2_champion().detect_translated_cycler(100, 2), None);
+    }
+
+    #[test]
+    fn detect_translated_cycler_is_sound() {
+        let tm = TuringMachine::<2>::from_standard_text("0LB0LZ_1RA1LB").unwrap();
+        assert!(tm.run(100).is_some());
+        assert_eq!(tm.detect_translated_cycler(100, 0), None);
+
+        for tm in enumerate_machines::<2>() {
+            if let Some(outcome) = tm.run(100) {
+                for window in 0..4 {
+                    assert_eq!(
+                        tm.detect_translated_cycler(100, window),
+                        None,
+                        "{} halts after {} steps",
+                        tm,
+                        outcome.steps
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn run_accelerated_matches_run() {
+        let tm = TuringMachine::<4>::from_standard_text("1RB1LB_1LA0LC_1RZ1LD_1RD0RA").unwrap();
+        assert_eq!(
+            tm.run_accelerated(1000),
+            Some(RunOutcome {
+                steps: 107,
+                ones: 13,
+                space: 14,
+                halt_state: State::Halt,
+                writes_of: [33, 74],
+            })
+        );
+        assert_eq!(tm.run_accelerated(1000), tm.run(1000));
+        assert_eq!(tm.run_accelerated(106), None);
+
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..1000 {
+            let tm = random_machine::<3>(&mut rng);
+            assert_eq!(tm.run_accelerated(500), tm.run(500));
+        }
+    }
+
+    #[test]
+    fn run_counts_writes() {
+        let tm = TuringMachine::<2>::from_standard_text("1RB1RZ_0LA1RZ").unwrap();
+        assert_eq!(tm.run(100).unwrap().writes_of, [1, 2]);
+
+        // Shifts over runs of symbols count as rewrites
+        let tm = TuringMachine::<4>::from_standard_text("1RB1LB_1LA0LC_1RZ1LD_1RD0RA").unwrap();
+        for outcome in &[tm.run(1000).unwrap(), tm.run_accelerated(1000).unwrap()] {
+            assert_eq!(outcome.writes_of.iter().sum::<usize>(), outcome.steps);
+            assert_eq!(outcome.writes_of, [33, 74]);
+        }
+    }
+
+    #[test]
+    fn run_accelerated_long_sweeps() {
+        let tm =
+            TuringMachine::<5>::from_standard_text("1RB1LC_1RC1RB_1RD0LE_1LA1LD_1RZ0LA").unwrap();
+
+        assert_eq!(
+            tm.run_accelerated(50_000_000),
+            Some(RunOutcome {
+                steps: 47_176_870,
+                ones: 4098,
+                space: 12_289,
+                halt_state: State::Halt,
+                writes_of: [20_405, 47_156_465],
+            })
+        );
+
+        // A single step applies one transition while a sweep applies one for
+        // the whole block
+        let (outcome, transitions) = tm.run_accelerated_counting(50_000_000);
+        assert_eq!(transitions, 67_346);
+        assert!(transitions * 500 < outcome.unwrap().steps);
+    }
+
+    #[test]
+    fn more_than_seven_states() {
+        let tm = TuringMachine::<15>::from_standard_text(
+            "1RB1RZ_1RC1RZ_1RD1RZ_1RE1RZ_1RF1RZ_1RG1RZ_1RH1RZ_1RI1RZ_\
+             1RJ1RZ_1RK1RZ_1RL1RZ_1RM1RZ_1RN1RZ_1RO1RZ_1RZ1RZ",
+        )
+        .unwrap();
+
+        assert!(tm.to_string().starts_with("1RB 1RZ 1RC 1RZ"));
+        assert!(tm.to_string().ends_with("1RO 1RZ 1RZ 1RZ"));
+        assert_eq!(
+            tm.run(100),
+            Some(RunOutcome {
+                steps: 15,
+                ones: 15,
+                space: 16,
+                halt_state: State::Halt,
+                writes_of: [0, 15],
+            })
+        );
+    }
+
+    #[cfg(feature = "thread-rng")]
+    #[test]
+    fn generate_busy_beaver_halts() {
+        assert!(generate_busy_beaver::<2>(100).run(100).is_some());
+        assert!(generate_busy_beaver::<3>(100).run(100).is_some());
+        assert!(generate_busy_beaver::<4>(100).run(100).is_some());
+    }
+
+    #[test]
+    fn generate_busy_beaver_never_halting_early() {
+        let config = GeneratorConfig {
+            halt_probability: Some(0.0),
+            ..GeneratorConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(65);
+
+        for _ in 0..20 {
+            let tm = generate_busy_beaver_with_config::<3, _>(100, &config, &mut rng);
+            let halting = tm
+                .transitions()
+                .iter()
+                .flat_map(|t| (0..2).map(move |symbol| t.get_action_of(symbol).2))
+                .filter(|state| state.is_halting())
+                .count();
+
+            // Only the last undefined transition halts so every transition is
+            // specified
+            assert_eq!(halting, 1);
+            assert!(tm.run(100).is_some());
+        }
+    }
+
+    #[test]
+    fn generate_busy_beaver_on_input() {
+        let input = [1, 1, 0, 1, 1];
+        let config = GeneratorConfig {
+            input: &input,
+            ..GeneratorConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for _ in 0..20 {
+            let tm = generate_busy_beaver_with_config::<3, _>(100, &config, &mut rng);
+            assert!(tm.run_from(&input, 0, 100).is_some(), "{}", tm);
+        }
+    }
+
+    #[test]
+    fn generate_busy_beaver_symbol_one_probability() {
+        let written = |probability| {
+            let config = GeneratorConfig {
+                symbol_one_probability: probability,
+                ..GeneratorConfig::default()
+            };
+            let mut rng = StdRng::seed_from_u64(80);
+
+            (0..20)
+                .flat_map(|_| {
+                    let tm = generate_busy_beaver_with_config::<3, _>(100, &config, &mut rng);
+                    (0..2)
+                        .flat_map(|symbol| {
+                            tm.transitions()
+                                .iter()
+                                .map(move |t| t.get_action_of(symbol))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .filter(|&(_, _, state)| !state.is_halting())
+                .map(|(written, _, _)| written)
+                .collect::<Vec<u8>>()
+        };
+
+        assert!(written(1.0).iter().all(|&symbol| symbol == 1));
+        assert!(written(0.5).contains(&0));
+    }
+
+    #[test]
+    #[should_panic(expected = "the probability of writing a 1 must be between 0 and 1")]
+    fn generate_busy_beaver_invalid_symbol_one_probability() {
+        let config = GeneratorConfig {
+            symbol_one_probability: 1.5,
+            ..GeneratorConfig::default()
+        };
+
+        let _ =
+            generate_busy_beaver_with_config::<2, _>(100, &config, &mut StdRng::seed_from_u64(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "the halt probability must be between 0 and 1")]
+    fn generate_busy_beaver_invalid_halt_probability() {
+        let config = GeneratorConfig {
+            halt_probability: Some(-0.5),
+            ..GeneratorConfig::default()
+        };
+
+        let _ =
+            generate_busy_beaver_with_config::<2, _>(100, &config, &mut StdRng::seed_from_u64(0));
+    }
+
+    #[test]
+    fn one_state_machine() {
+        let tm = TuringMachine::<1>::from_standard_text("1RZ0LA").unwrap();
+
+        assert_eq!(
+            tm.run(100),
+            Some(RunOutcome {
+                steps: 1,
+                ones: 1,
+                space: 2,
+                halt_state: State::Halt,
+                writes_of: [0, 1],
+            })
+        );
+        assert_eq!(tm.to_standard_text(), "1RZ0LA");
+        assert_eq!(tm.canonicalize(), tm);
+        assert_eq!(
+            TuringMachine::<1>::from_standard_text("1RA1RZ")
+                .unwrap()
+                .run(100),
+            None
+        );
+
+        let generated = generate_busy_beaver_seeded::<1, _>(100, &mut StdRng::seed_from_u64(1));
+        assert_eq!(generated.run(100).map(|outcome| outcome.ones), Some(1));
+    }
+
+    #[test]
+    fn generate_busy_beaver_full_machines() {
+        fn assert_full<const N: usize>(seed: u64) {
+            let tm = generate_busy_beaver_seeded::<N, _>(1000, &mut StdRng::seed_from_u64(seed));
+
+            assert!(tm.run(1000).is_some(), "{}", tm);
+            assert_eq!(tm.transition(State::A).action(0).next_state(), State::B);
+            // Transitions are only added when reached so every state is visited
+            assert_eq!(tm.reachable_states(1000).len(), N, "{}", tm);
+        }
+
+        for seed in 0..10 {
+            assert_full::<2>(seed);
+            assert_full::<3>(seed);
+            assert_full::<4>(seed);
+        }
+    }
+
+    #[test]
+    fn partial_next_state_limit() {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); 3]);
+        assert_eq!(machine.next_state_limit(State::A), State::B);
+
+        machine.add_transition(State::A, 0, Action::new(1, Direction::Right, State::B));
+        assert_eq!(machine.next_state_limit(State::B), State::C);
+        assert_eq!(machine.next_state_limit(State::A), State::B);
+
+        machine.add_transition(State::C, 0, Action::new(1, Direction::Left, State::A));
+        assert_eq!(machine.next_state_limit(State::A), State::C);
+        assert_eq!(
+            PartialTuringMachine::new([PartialTransition::default(); 1]).next_state_limit(State::A),
+            State::A
+        );
+    }
+
+    #[test]
+    fn generate_busy_beaver_default_config() {
+        let seeded = generate_busy_beaver_seeded::<3, _>(100, &mut StdRng::seed_from_u64(7));
+        let configured = generate_busy_beaver_with_config::<3, _>(
+            100,
+            &GeneratorConfig::default(),
+            &mut StdRng::seed_from_u64(7),
+        );
+
+        assert_eq!(seeded, configured);
+    }
+
+    #[test]
+    fn generate_busy_beaver_seeded_is_reproducible() {
+        let first = generate_busy_beaver_seeded::<4, _>(1000, &mut StdRng::seed_from_u64(42));
+        let second = generate_busy_beaver_seeded::<4, _>(1000, &mut StdRng::seed_from_u64(42));
+
+        assert_eq!(first.to_standard_text(), second.to_standard_text());
+    }
+
+    #[test]
+    fn generate_busy_beaver_seeded_is_deterministic() {
+        for seed in 0..20 {
+            let machines = (0..2)
+                .map(|_| generate_busy_beaver_seeded::<3, _>(100, &mut StdRng::seed_from_u64(seed)))
+                .collect::<Vec<TuringMachine<3>>>();
+
+            assert_eq!(machines[0], machines[1]);
+            assert!(machines[0].run(100).is_some());
+        }
+    }
+
+    #[cfg(feature = "thread-rng")]
+    #[test]
+    fn busy_beaver_stream_yields_candidates() {
+        let machines = busy_beaver_stream::<3>(100)
+            .take(10)
+            .collect::<Vec<TuringMachine<3>>>();
+
+        assert_eq!(machines.len(), 10);
+        assert!(machines.iter().all(|tm| tm.run(100).is_some()));
+        assert!(machines.iter().collect::<HashSet<_>>().len() > 1);
+    }
+
+    #[test]
+    fn canonicalize_relabelings() {
+        let tm = TuringMachine::<3>::from_standard_text("1RB1RZ_0RC1RB_1LC1LA").unwrap();
+        let relabeled = TuringMachine::<3>::from_standard_text("1RC1RZ_1LB1LA_0RB1RC").unwrap();
+
+        assert_eq!(
+            tm.canonicalize().to_standard_text(),
+            relabeled.canonicalize().to_standard_text()
+        );
+        assert_eq!(
+            relabeled.canonicalize().to_standard_text(),
+            "1RB1RZ_0RC1RB_1LC1LA"
+        );
+        assert_eq!(relabeled.canonicalize().run(100), relabeled.run(100));
+    }
+
+    #[test]
+    fn normalized_text_relabelings() {
+        let tm = TuringMachine::<3>::from_standard_text("1RB1RZ_0RC1RB_1LC1LA").unwrap();
+        let relabeled = TuringMachine::<3>::from_standard_text("1RC1RZ_1LB1LA_0RB1RC").unwrap();
+
+        assert_eq!(tm.normalized_text(), "1RB1RZ_0RC1RB_1LC1LA");
+        assert_eq!(relabeled.normalized_text(), tm.normalized_text());
+
+        // C and D are never visited but can be reached through the transitions
+        let tm = TuringMachine::<4>::from_standard_text("1RB1RZ_1LA1RD_1LC1RZ_1LD1RC").unwrap();
+        let relabeled =
+            TuringMachine::<4>::from_standard_text("1RB1RZ_1LA1RC_1LC1RD_1LD1RZ").unwrap();
+
+        assert_eq!(tm.normalized_text(), relabeled.normalized_text());
+        assert_eq!(relabeled.normalized_text(), relabeled.to_standard_text());
+    }
+
+    #[test]
+    fn canonical_key_relabeled_machine() {
+        let tm = three_state_machine();
+        let relabeled = TuringMachine::<3>::from_standard_text("1RC1RZ_1LB1LA_0RB1RC").unwrap();
+
+        assert_ne!(tm, relabeled);
+        assert_eq!(tm.canonical_key(), relabeled.canonical_key());
+        assert_ne!(
+            tm.canonical_key(),
+            TuringMachine::<3>::from_standard_text("1RB1RZ_0RC1RB_1LC1LB")
+                .unwrap()
+                .canonical_key()
+        );
+    }
+
+    #[test]
+    fn behaviorally_equal() {
+        let tm = TuringMachine::<4>::from_standard_text("1RB1LB_1LA0LC_1RZ1LD_1RD0RA").unwrap();
+        let relabeled =
+            TuringMachine::<4>::from_standard_text("1RC1LC_1RZ1LD_1LA0LB_1RD0RA").unwrap();
+
+        assert!(tm.behaviorally_equal(&tm.canonicalize(), 1000));
+        assert!(tm.behaviorally_equal(&relabeled, 1000));
+        assert!(relabeled.behaviorally_equal(&tm, 1000));
+        assert!(bb2_champion().behaviorally_equal(&bb2_champion().canonicalize(), 100));
+
+        // Same behavior until the halting transition
+        let other = TuringMachine::<2>::from_standard_text("1RB1LB_1LA1LZ").unwrap();
+        assert!(!bb2_champion().behaviorally_equal(&other, 100));
+        assert!(bb2_champion().behaviorally_equal(&other, 5));
+
+        let mirror = bb2_champion().mirror();
+        assert!(!bb2_champion().behaviorally_equal(&mirror, 100));
+    }
+
+    #[test]
+    fn canonical_key_is_stable() {
+        // FNV-1a hash of the packed actions 1RB, 1LB, 1LA and 1RZ
+        assert_eq!(bb2_champion().canonical_key(), 0xb05d_5aca_8362_6cf5);
+    }
+
+    #[test]
+    fn structural_signature_ignores_labels() {
+        let bb3 = TuringMachine::<3>::from_standard_text("1RB1RZ_0RC1RB_1LC1LA").unwrap();
+        let relabeled = TuringMachine::<3>::from_standard_text("1RC1RZ_1LB1LA_0RB1RC").unwrap();
+
+        assert_eq!(bb3.structural_signature(), relabeled.structural_signature());
+        assert_eq!(
+            bb3.structural_signature(),
+            bb3.canonicalize().structural_signature()
+        );
+
+        for text in &[
+            "1RB1RZ_0RC1RB_1LC0LA",
+            "1RB1RY_0RC1RB_1LC1LA",
+            "1LB1RZ_0RC1RB_1LC1LA",
+        ] {
+            let other = TuringMachine::<3>::from_standard_text(text).unwrap();
+            assert_ne!(bb3.structural_signature(), other.structural_signature());
+        }
+        assert_ne!(
+            bb3.structural_signature(),
+            bb3.mirror().structural_signature()
+        );
+
+        for tm in enumerate_machines::<2>().step_by(97) {
+            assert_eq!(
+                tm.structural_signature(),
+                tm.canonicalize().structural_signature(),
+                "{}",
+                tm
+            );
+        }
+    }
+
+    #[test]
+    fn mirror_final_tapes() {
+        fn assert_mirrored<const N: usize>(text: &str) {
+            let tm = TuringMachine::<N>::from_standard_text(text).unwrap();
+            let (mut tape, mut mirror_tape) = (Tape::new(), Tape::new());
+            tm.run_on(&mut tape, 100).unwrap();
+            tm.mirror().run_on(&mut mirror_tape, 100).unwrap();
+
+            // Both tapes are reflected about position 0 so they only match
+            // relative to their heads
+            let reflected = tape.mirror();
+            for offset in -10..=10 {
+                assert_eq!(mirror_tape.peek(offset), reflected.peek(offset), "{}", text);
+            }
+        }
+
+        assert_mirrored::<2>("1RB1LB_1LA1RZ");
+        assert_mirrored::<3>("1RB1RZ_0RC1RB_1LC1LA");
+    }
+
+    #[test]
+    fn reachable_states_first_visit_order() {
+        let tm = TuringMachine::<3>::from_standard_text("1RC1RZ_1LA1RZ_0LB1RZ").unwrap();
+
+        assert_eq!(tm.reachable_states(100), vec![State::A, State::C, State::B]);
+        assert_eq!(tm.reachable_states(1), vec![State::A, State::C]);
+        assert_eq!(tm.reachable_states(0), vec![State::A]);
+    }
+
+    #[test]
+    fn reachable_states_dead_state() {
+        let tm = TuringMachine::<4>::from_standard_text("1RB1LB_1LA1RZ_1RD0LC_1LC1RA").unwrap();
+        let states = tm.reachable_states(100);
+
+        assert_eq!(states, vec![State::A, State::B]);
+        assert!(!states.contains(&State::D));
+    }
+
+    #[test]
+    fn canonicalize_unvisited_states() {
+        let tm = TuringMachine::<4>::from_standard_text("1RD1RZ_1LA0RB_1RC1RC_1LA1RZ").unwrap();
+
+        assert_eq!(
+            tm.canonicalize().to_standard_text(),
+            "1RB1RZ_1LA1RZ_1LA0RC_1RD1RD"
+        );
+    }
+
+    #[test]
+    fn run_with_observer() {
+        let mut steps = Vec::new();
+        let outcome = bb2_champion().run_with(100, |step| steps.push(*step));
+
+        assert_eq!(
+            outcome,
+            Some(RunOutcome {
+                steps: 6,
+                ones: 4,
+                space: 4,
+                halt_state: State::Halt,
+                writes_of: [0, 6],
+            })
+        );
+        assert_eq!(steps.len(), 6);
+        assert_eq!(
+            steps[0],
+            StepInfo {
+                step: 0,
+                state: State::A,
+                read: 0,
+                written: 1,
+                direction: Direction::Right,
+                head: 0,
+            }
+        );
+        assert_eq!(
+            steps[5],
+            StepInfo {
+                step: 5,
+                state: State::B,
+                read: 1,
+                written: 1,
+                direction: Direction::Right,
+                head: -1,
+            }
+        );
+    }
+
+    #[test]
+    fn run_with_observer_not_halting() {
+        let tm = TuringMachine::<2>::from_standard_text("1RA1RZ_1RZ1RZ").unwrap();
+        let mut count = 0;
+
+        assert_eq!(tm.run_with(50, |_| count += 1), None);
+        assert_eq!(count, 50);
+    }
+
+    #[test]
+    fn stepper() {
+        let tm = TuringMachine::<4>::from_standard_text("1RB1LB_1LA0LC_1RZ1LD_1RD0RA").unwrap();
+        let steps = tm.stepper().collect::<Vec<StepInfo>>();
+
+        assert_eq!(steps.len(), tm.run(1000).unwrap().steps);
+
+        let mut observed = Vec::new();
+        tm.run_with(1000, |step| observed.push(*step));
+        assert_eq!(steps, observed);
+    }
+
+    #[test]
+    fn stepper_pause_and_resume() {
+        let tm = TuringMachine::<2>::from_standard_text("1RA1RZ_1RZ1RZ").unwrap();
+        let mut stepper = tm.stepper();
+
+        assert_eq!(stepper.by_ref().take(10).count(), 10);
+        assert_eq!(stepper.step(), 10);
+        assert_eq!(stepper.state(), State::A);
+        assert_eq!(stepper.tape().count_ones(), 10);
+        assert_eq!(stepper.next().map(|step| step.head), Some(10));
+
+        let bb2 = bb2_champion();
+        let mut stepper = bb2.stepper();
+        assert_eq!(stepper.by_ref().count(), 6);
+        assert!(stepper.state().is_halting());
+        assert_eq!(stepper.next(), None);
+    }
+
+    #[test]
+    fn partial_is_n_state_full() {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); 3]);
+        assert!(!machine.is_n_state_full());
+
+        machine.add_transition(State::A, 0, Action::new(1, Direction::Right, State::B));
+        machine.add_transition(State::B, 1, Action::new(0, Direction::Left, State::A));
+        assert!(!machine.is_n_state_full());
+
+        machine.add_transition(State::C, 0, Action::new(1, Direction::Left, State::A));
+        assert!(machine.is_n_state_full());
+        assert!(!machine.is_complete());
+    }
+
+    #[test]
+    fn partial_is_complete() {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); 3]);
+        assert_eq!(machine.count_specified_transitions(), 0);
+        assert!(!machine.is_complete());
+
+        machine.add_transition(State::A, 0, Action::new(1, Direction::Right, State::B));
+        machine.add_transition(State::B, 1, Action::new(0, Direction::Left, State::C));
+        machine.add_transition(State::C, 0, Action::new(1, Direction::Left, State::A));
+        assert_eq!(machine.count_specified_transitions(), 3);
+        assert!(!machine.is_complete());
+
+        machine.add_transition(State::A, 1, Action::new(1, Direction::Left, State::C));
+        machine.add_transition(State::B, 0, Action::new(1, Direction::Right, State::A));
+        machine.add_transition(State::C, 1, Action::new(1, Direction::Right, State::Halt));
+        assert_eq!(machine.count_specified_transitions(), 6);
+        assert!(machine.is_complete());
+    }
+
+    #[test]
+    fn detects_spinout() {
+        let right = TuringMachine::<2>::from_standard_text("1RB1LA_0RA1RZ").unwrap();
+        let left = TuringMachine::<2>::from_standard_text("1LB0RZ_0LA1RZ").unwrap();
+        let delayed = TuringMachine::<2>::from_standard_text("0LB0LZ_1RB1RZ").unwrap();
+
+        assert!(right.detects_spinout(10));
+        assert!(left.detects_spinout(10));
+        assert!(delayed.detects_spinout(10));
+        assert!(!bb2_champion().detects_spinout(100));
+        assert!(!three_state_machine().detects_spinout(100));
+    }
+
+    #[test]
+    fn detects_spinout_never_halts() {
+        for machine in enumerate_machines::<2>() {
+            if machine.detects_spinout(20) {
+                assert_eq!(machine.run(100), None, "{}", machine);
+            }
+        }
+    }
+
+    #[test]
+    fn returns_to_origin() {
+        let bb2 = bb2_champion();
+        assert!(bb2.returns_to_origin(100));
+        assert!(!bb2.returns_to_origin(1));
+
+        let spinner = TuringMachine::<1>::from_standard_text("0RA1RZ").unwrap();
+        assert!(!spinner.returns_to_origin(100));
+
+        let halter = TuringMachine::<1>::from_standard_text("1RZ0LA").unwrap();
+        assert!(!halter.returns_to_origin(100));
+    }
+
+    #[test]
+    fn partial_into_complete() {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); 3]);
+        machine.add_transition(State::A, 0, Action::new(1, Direction::Right, State::B));
+        machine.add_transition(State::B, 1, Action::new(0, Direction::Left, State::C));
+        machine.add_transition(State::C, 0, Action::new(1, Direction::Left, State::A));
+
+        let complete = machine.clone().into_complete();
+
+        assert_eq!(complete.to_standard_text(), "1RB1RZ_0RZ0LC_1LA1RZ");
+        assert_eq!(complete, TuringMachine::from(machine));
+    }
+
+    #[test]
+    fn partial_quick_reject() {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); 2]);
+        assert!(!machine.quick_reject());
+
+        machine.add_transition(State::A, 0, Action::new(0, Direction::Right, State::A));
+        assert!(machine.quick_reject());
+
+        machine.add_transition(State::A, 0, Action::new(1, Direction::Right, State::B));
+        machine.add_transition(State::B, 0, Action::new(0, Direction::Right, State::A));
+        assert!(machine.quick_reject());
+
+        machine.add_transition(State::B, 0, Action::new(1, Direction::Left, State::A));
+        assert!(!machine.quick_reject());
+
+        machine.add_transition(State::B, 0, Action::new(1, Direction::Right, State::Halt));
+        assert!(!machine.quick_reject());
+    }
+
+    #[test]
+    fn partial_run_undefined_transition() {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); 2]);
+        machine.add_transition(State::A, 0, Action::new(1, Direction::Right, State::B));
+        machine.add_transition(State::B, 0, Action::new(1, Direction::Left, State::B));
+
+        let error = machine.run(100).unwrap_err();
+        assert_eq!(
+            error,
+            UndefinedTransition {
+                state: State::B,
+                symbol: 1,
+                step: 2,
+                head: 0
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "undefined transition for state B on symbol 1 at step 2 (head at 0)"
+        );
+    }
+
+    #[test]
+    fn partial_run_undefined_transition_position() {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); 3]);
+        machine.add_transition(State::A, 0, Action::new(1, Direction::Left, State::B));
+        machine.add_transition(State::B, 0, Action::new(1, Direction::Left, State::C));
+        machine.add_transition(State::C, 0, Action::new(1, Direction::Right, State::B));
+
+        let error = machine.run(100).unwrap_err();
+        assert_eq!(error.state, State::B);
+        assert_eq!(error.symbol, 1);
+        assert_eq!(error.step, 3);
+        assert_eq!(error.head, -1);
+    }
+
+    #[test]
+    fn partial_run_halting() {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); 2]);
+        machine.add_transition(State::A, 0, Action::new(1, Direction::Right, State::B));
+        machine.add_transition(State::B, 0, Action::new(1, Direction::Left, State::Halt));
+
+        assert_eq!(machine.run(100), Ok(Some(2)));
+    }
+
+    #[test]
+    fn partial_run_from() {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); 2]);
+        machine.add_transition(State::A, 0, Action::new(1, Direction::Right, State::B));
+        machine.add_transition(State::B, 0, Action::new(1, Direction::Left, State::Halt));
+
+        let error = machine.run_from(&[1, 0], 0, 100).unwrap_err();
+        assert_eq!((error.state, error.symbol, error.step), (State::A, 1, 0));
+
+        machine.add_transition(State::A, 1, Action::new(0, Direction::Right, State::A));
+        assert_eq!(machine.run_from(&[1, 0], 0, 100), Ok(Some(2)));
+        assert_eq!(machine.run_from(&[], 0, 100), machine.run(100));
+    }
+
+    #[test]
+    fn partial_run_halt_on_undefined() {
+        let mut machine = PartialTuringMachine::new([PartialTransition::default(); 3]);
+        machine.add_transition(State::A, 0, Action::new(1, Direction::Right, State::B));
+        machine.add_transition(State::B, 0, Action::new(1, Direction::Left, State::C));
+        machine.add_transition(State::C, 1, Action::new(1, Direction::Left, State::B));
+
+        let outcome = machine.run_halt_on_undefined(100).unwrap();
+        assert_eq!(outcome.steps, 5);
+        assert_eq!(outcome.ones, 3);
+        assert_eq!(outcome.halt_state, State::Halt);
+        assert!(machine.run(100).is_err());
+
+        machine.add_transition(State::C, 0, Action::new(0, Direction::Left, State::C));
+        assert_eq!(machine.run_halt_on_undefined(100), None);
+    }
+
+    #[test]
+    fn run_space_right_sweep() {
+        let tm = TuringMachine::<3>::from_standard_text("1RB1RZ_0RC1RZ_1RZ1RZ").unwrap();
+        let outcome = tm.run(100).unwrap();
+
+        assert_eq!(outcome.steps, 3);
+        assert_eq!(outcome.ones, 2);
+        assert_eq!(outcome.space, 4);
+    }
+
+    #[test]
+    fn try_new_rejects_unknown_state() {
+        let transitions = [
+            Transition::new(
+                Action::new(1, Direction::Right, State::B),
+                Action::new(1, Direction::Left, State::B),
+            ),
+            Transition::new(
+                Action::new(1, Direction::Left, State::C),
+                Action::new(1, Direction::Right, State::Halt),
+            ),
+        ];
+
+        assert_eq!(
+            TuringMachine::<2>::try_new(transitions).unwrap_err(),
+            InvalidMachine {
+                state: State::B,
+                symbol: 0,
+                target: State::C,
+            }
+        );
+    }
+
+    #[test]
+    fn try_new_accepts_valid_machine() {
+        assert!(TuringMachine::<2>::try_new(bb2_champion().transitions).is_ok());
+    }
+
+    #[test]
+    fn transition_accessors() {
+        let transitions = [
+            Transition::new(
+                Action::new(1, Direction::Right, State::B),
+                Action::new(1, Direction::Left, State::B),
+            ),
+            Transition::new(
+                Action::new(1, Direction::Left, State::A),
+                Action::new(1, Direction::Right, State::Halt),
+            ),
+        ];
+        let tm = TuringMachine::new(transitions);
+
+        assert_eq!(tm.transitions(), &transitions);
+        assert_eq!(tm.transition(State::A), &transitions[0]);
+        assert_eq!(tm.transition(State::B), &transitions[1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the halting state has no transition")]
+    fn transition_of_halting_state() {
+        let _ = bb2_champion().transition(State::Halt);
+    }
+
+    #[test]
+    fn from_standard_text_rejects_unknown_state() {
+        assert_eq!(
+            TuringMachine::<2>::from_standard_text("1RB1LB_1LC1RZ").unwrap_err(),
+            ParseError::InvalidState("C".to_string())
+        );
+    }
+
+    #[test]
+    fn hash_set_dedup() {
+        let machine = bb2_champion();
+        let mut machines = HashSet::new();
+        machines.insert(machine.clone());
+        machines.insert(machine);
+        assert_eq!(machines.len(), 1);
+
+        machines.insert(bb2_champion().mirror());
+        assert_eq!(machines.len(), 2);
+    }
+
+    #[test]
+    fn run_reports_halt_state() {
+        let bb2 = TuringMachine::<2>::from_standard_text("1RB1LB_1LA1RZ").unwrap();
+        let secondary = TuringMachine::<2>::from_standard_text("1RB1LB_1LA1RY").unwrap();
+        let either = TuringMachine::<2>::from_standard_text("1RB1RY_1LA1RZ").unwrap();
+
+        assert_eq!(bb2.run(100).unwrap().halt_state, State::Halt0);
+        assert_eq!(
+            secondary.run(100),
+            Some(RunOutcome {
+                steps: 6,
+                ones: 4,
+                space: 4,
+                halt_state: State::Halt1,
+                writes_of: [0, 6],
+            })
+        );
+        assert_eq!(secondary.to_standard_text(), "1RB1LB_1LA1RY");
+        assert!(secondary
+            .to_dot()
+            .contains("    B -> Halt1 [label=\"1/1,R\"];"));
+        assert!(secondary
+            .to_dot()
+            .contains("    Halt1 [shape=doublecircle];"));
+
+        assert_eq!(either.run(100).unwrap().halt_state, State::Halt1);
+        assert_eq!(
+            either.run_from(&[0, 1], 0, 100).unwrap().halt_state,
+            State::Halt
+        );
+    }
+
+    #[test]
+    fn to_dot() {
+        let dot = bb2_champion().to_dot();
+
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.ends_with('}'));
+        assert_eq!(dot.matches("->").count(), 4);
+        assert!(dot.contains("    A -> B [label=\"0/1,R\"];"));
+        assert!(dot.contains("    B -> A [label=\"0/1,L\"];"));
+        assert!(dot.contains("    B -> Halt [label=\"1/1,R\"];"));
+        assert!(dot.contains("    Halt [shape=doublecircle];"));
+
+        let tm = TuringMachine::<4>::from_standard_text("1RB1LB_1LA0LC_1RZ1LD_1RD0RA").unwrap();
+        assert_eq!(tm.to_dot().matches("->").count(), 8);
+        assert!(tm.to_dot().contains("    D -> D [label=\"0/1,R\"];"));
+    }
+
+    #[test]
+    fn steps_to_first_write_immediate() {
+        assert_eq!(bb2_champion().steps_to_first_write(100), Some(1));
+    }
+
+    #[test]
+    fn steps_to_first_write_delayed() {
+        let tm = TuringMachine::<2>::from_standard_text("0RB1RZ_1LA1LA").unwrap();
+        assert_eq!(tm.steps_to_first_write(100), Some(2));
+        assert_eq!(tm.steps_to_first_write(1), None);
+
+        let tm = TuringMachine::<2>::from_standard_text("0RB1RZ_0RZ1RZ").unwrap();
+        assert_eq!(tm.steps_to_first_write(100), None);
+    }
+
+    #[test]
+    fn cmp_by_productivity() {
+        let trivial = TuringMachine::<2>::from_standard_text("1RZ1RZ_1RZ1RZ").unwrap();
+        let runaway = TuringMachine::<2>::from_standard_text("1RA1RZ_1RZ1RZ").unwrap();
+
+        assert_eq!(bb2_champion().cmp_by_ones(&trivial, 100), Ordering::Greater);
+        assert_eq!(
+            bb2_champion().cmp_by_steps(&trivial, 100),
+            Ordering::Greater
+        );
+        assert_eq!(trivial.cmp_by_ones(&runaway, 100), Ordering::Greater);
+        assert_eq!(runaway.cmp_by_steps(&trivial, 100), Ordering::Less);
+        assert_eq!(
+            bb2_champion().cmp_by_ones(&bb2_champion(), 100),
+            Ordering::Equal
+        );
+
+        let mut machines = [trivial, bb2_champion(), runaway];
+        machines.sort_by(|a, b| b.cmp_by_ones(a, 100));
+        assert_eq!(machines[0], bb2_champion());
+    }
+
+    #[test]
+    fn mirror() {
+        assert_eq!(bb2_champion().mirror().to_standard_text(), "1LB1RB_1RA1LZ");
+        assert_eq!(
+            bb2_champion().mirror().mirror().to_standard_text(),
+            bb2_champion().to_standard_text()
+        );
+    }
+
+    #[test]
+    fn swap_symbols() {
+        assert_eq!(
+            bb2_champion().swap_symbols().to_standard_text(),
+            "0LB0RB_0RZ0LA"
+        );
+        assert_eq!(bb2_champion().swap_symbols().swap_symbols(), bb2_champion());
+
+        for tm in enumerate_machines::<2>().take(1000) {
+            assert_eq!(tm.swap_symbols().swap_symbols(), tm);
+        }
+    }
+
+    #[test]
+    fn mirror_preserves_outcome() {
+        for tm in enumerate_machines::<2>() {
+            assert_eq!(tm.mirror().run(50), tm.run(50));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let machine =
+            TuringMachine::<4>::from_standard_text("1RB1LB_1LA0LC_1RZ1LD_1RD0RA").unwrap();
+
+        let json = serde_json::to_string(&machine).unwrap();
+        let deserialized: TuringMachine<4> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized.to_standard_text(), machine.to_standard_text());
+        assert_eq!(deserialized.run(1000), machine.run(1000));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_state_count_mismatch() {
+        let json = serde_json::to_string(&bb2_champion()).unwrap();
+
+        assert!(serde_json::from_str::<TuringMachine<3>>(&json).is_err());
+    }
+}
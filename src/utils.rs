@@ -1,4 +1,4 @@
-use std::ops::RangeInclusive;
+use core::ops::RangeInclusive;
 
 /// Maps an `RangeInclusive<T>` to `RangeInclusive<U>` by applying a
 /// function to the bounds.